@@ -0,0 +1,320 @@
+//! # Gateway Artifact Fetching
+//!
+//! `read_screenshot` used to hardcode image MIME sniffing, base64-inline whole files, and only
+//! fall back to the Gateway for paths under `.forgeai/`. This generalizes that into a reusable
+//! fetch any command can use for logs, generated documents, or model files: local file first,
+//! then the Gateway's `/api/files/` endpoint, with a content-addressed on-disk cache keyed by
+//! ETag/Last-Modified (so an unchanged file skips the network on repeat fetches), HTTP range
+//! requests for large files (fetched in chunks, each retried if it fails), and, once a file
+//! crosses `STREAM_THRESHOLD_BYTES`, a local temp/cache file path instead of inline bytes. A large
+//! download goes to disk chunk by chunk only when the Gateway accepts range requests; otherwise
+//! its whole body is read into memory before being written out.
+
+use std::path::{Path, PathBuf};
+
+/// Above this size, `fetch_artifact` returns a local path instead of inline bytes. The download
+/// is written in range-request chunks when the Gateway accepts ranges; otherwise the whole body
+/// is buffered in memory first.
+const STREAM_THRESHOLD_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Chunk size for ranged downloads of large artifacts.
+const CHUNK_SIZE: u64 = 2 * 1024 * 1024;
+
+const MAX_CHUNK_RETRIES: u32 = 3;
+
+/// A fetched artifact: either inline bytes (small files) or a path to a temp/cache file on disk
+/// (anything over `STREAM_THRESHOLD_BYTES`), never both.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Artifact {
+    pub mime: String,
+    pub bytes: Option<Vec<u8>>,
+    pub local_path: Option<String>,
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct FetchOptions {
+    /// Skip the local cache's ETag/Last-Modified check and always revalidate with the Gateway.
+    #[serde(default)]
+    pub force_refresh: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct CacheMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    mime: String,
+    size: u64,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("forgeai-companion").join("artifacts"))
+}
+
+/// Content-addressed cache key for (gateway_url, path) — stable across runs, and distinct
+/// Gateways can't collide even if they happen to serve the same relative path.
+fn cache_key(gateway_url: &str, rel_path: &str) -> String {
+    use sha2::Digest as _;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(gateway_url.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(rel_path.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn sniff_mime(path: &str) -> String {
+    let ext = path.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "png" => "image/png",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "txt" | "log" => "text/plain",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+/// Fetch `path`: try the local filesystem first (works when the Gateway runs on the same
+/// machine), then fall back to the Gateway's `/api/files/<rel>` endpoint for anything under a
+/// `.forgeai/` root, honoring the on-disk cache and streaming large files to a temp file.
+pub async fn fetch_artifact(
+    path: &str,
+    gateway_url: Option<&str>,
+    opts: &FetchOptions,
+) -> Result<Artifact, String> {
+    let mime = sniff_mime(path);
+
+    if let Ok(meta) = tokio::fs::metadata(path).await {
+        if meta.is_file() && meta.len() > STREAM_THRESHOLD_BYTES {
+            log::info!("Artifact found locally, returning its path: {}", path);
+            return Ok(Artifact { mime, bytes: None, local_path: Some(path.to_string()), from_cache: false });
+        }
+    }
+
+    if let Ok(data) = tokio::fs::read(path).await {
+        log::info!("Artifact loaded locally: {}", path);
+        return Ok(Artifact { mime, bytes: Some(data), local_path: None, from_cache: false });
+    }
+
+    let gw_url = gateway_url.ok_or_else(|| {
+        format!("Artifact not found locally and no Gateway configured: {}", path)
+    })?;
+
+    let normalized = path.replace("\\\\", "/").replace('\\', "/");
+    let idx = normalized
+        .find(".forgeai/")
+        .ok_or_else(|| format!("Not a Gateway-relative artifact path: {}", path))?;
+    let rel_path = &normalized[idx + 9..];
+    let url = format!("{}/api/files/{}", gw_url.trim_end_matches('/'), rel_path);
+
+    let key = cache_key(gw_url, rel_path);
+    let cache_paths = cache_dir().map(|dir| {
+        (dir.join(format!("{}.bin", key)), dir.join(format!("{}.meta.json", key)))
+    });
+
+    let cached_meta = if opts.force_refresh {
+        None
+    } else {
+        load_cache_meta(cache_paths.as_ref()).await
+    };
+
+    let client = reqwest::Client::new();
+    let mut head_req = client.get(&url).timeout(std::time::Duration::from_secs(30));
+    head_req = with_gateway_auth(head_req);
+    if let Some(meta) = &cached_meta {
+        head_req = conditional_headers(head_req, meta);
+    }
+
+    let resp = head_req.send().await.map_err(|e| format!("Gateway fetch failed: {}", e))?;
+
+    if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let (Some((cache_file, _)), Some(meta)) = (&cache_paths, &cached_meta) {
+            log::info!("Artifact cache hit (304 Not Modified): {}", rel_path);
+            return load_from_cache(cache_file, meta).await;
+        }
+    }
+
+    if !resp.status().is_success() {
+        return Err(format!("Gateway returned {}: {}", resp.status(), url));
+    }
+
+    let etag = header_str(&resp, "etag");
+    let last_modified = header_str(&resp, "last-modified");
+    let total_len = resp.content_length().unwrap_or(0);
+    let supports_range = resp
+        .headers()
+        .get("accept-ranges")
+        .map(|v| v == "bytes")
+        .unwrap_or(false);
+
+    let meta = CacheMeta { etag, last_modified, mime: mime.clone(), size: total_len };
+
+    if total_len > STREAM_THRESHOLD_BYTES && supports_range {
+        // Large + resumable: drop this first response's body and re-fetch in chunks so a dropped
+        // connection partway through only costs the current chunk, not the whole download.
+        drop(resp);
+        let dest = match &cache_paths {
+            Some((cache_file, _)) => cache_file.clone(),
+            None => std::env::temp_dir().join(format!("forgeai-artifact-{}", key)),
+        };
+        fetch_ranged(&client, &url, &dest, total_len).await?;
+        write_cache_meta(cache_paths.as_ref(), &meta).await;
+        return Ok(Artifact {
+            mime,
+            bytes: None,
+            local_path: Some(dest.to_string_lossy().to_string()),
+            from_cache: false,
+        });
+    }
+
+    let bytes = resp.bytes().await.map_err(|e| format!("Read bytes failed: {}", e))?;
+
+    if let Some((cache_file, _)) = &cache_paths {
+        let _ = tokio::fs::write(cache_file, &bytes).await;
+        write_cache_meta(cache_paths.as_ref(), &meta).await;
+    }
+
+    if bytes.len() as u64 > STREAM_THRESHOLD_BYTES {
+        let dest = std::env::temp_dir().join(format!("forgeai-artifact-{}", key));
+        tokio::fs::write(&dest, &bytes)
+            .await
+            .map_err(|e| format!("Temp file write failed: {}", e))?;
+        return Ok(Artifact {
+            mime,
+            bytes: None,
+            local_path: Some(dest.to_string_lossy().to_string()),
+            from_cache: false,
+        });
+    }
+
+    Ok(Artifact { mime, bytes: Some(bytes.to_vec()), local_path: None, from_cache: false })
+}
+
+fn with_gateway_auth(builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    if let Some(creds) = crate::connection::GatewayConnection::load_credentials() {
+        if let Some(ref token) = creds.auth_token {
+            return builder.header("Cookie", format!("forgeai_session={}", token));
+        }
+    }
+    builder
+}
+
+fn conditional_headers(builder: reqwest::RequestBuilder, meta: &CacheMeta) -> reqwest::RequestBuilder {
+    if let Some(etag) = &meta.etag {
+        builder.header("If-None-Match", etag.clone())
+    } else if let Some(lm) = &meta.last_modified {
+        builder.header("If-Modified-Since", lm.clone())
+    } else {
+        builder
+    }
+}
+
+fn header_str(resp: &reqwest::Response, name: &str) -> Option<String> {
+    resp.headers().get(name).and_then(|v| v.to_str().ok()).map(|s| s.to_string())
+}
+
+async fn load_cache_meta(cache_paths: Option<&(PathBuf, PathBuf)>) -> Option<CacheMeta> {
+    let (_, meta_file) = cache_paths?;
+    let raw = tokio::fs::read_to_string(meta_file).await.ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+async fn write_cache_meta(cache_paths: Option<&(PathBuf, PathBuf)>, meta: &CacheMeta) {
+    let Some((cache_file, meta_file)) = cache_paths else { return };
+    if let Some(parent) = cache_file.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+    if let Ok(json) = serde_json::to_string(meta) {
+        let _ = tokio::fs::write(meta_file, json).await;
+    }
+}
+
+async fn load_from_cache(cache_file: &Path, meta: &CacheMeta) -> Result<Artifact, String> {
+    if meta.size > STREAM_THRESHOLD_BYTES {
+        return Ok(Artifact {
+            mime: meta.mime.clone(),
+            bytes: None,
+            local_path: Some(cache_file.to_string_lossy().to_string()),
+            from_cache: true,
+        });
+    }
+    let bytes = tokio::fs::read(cache_file)
+        .await
+        .map_err(|e| format!("Cache read failed: {}", e))?;
+    Ok(Artifact { mime: meta.mime.clone(), bytes: Some(bytes), local_path: None, from_cache: true })
+}
+
+/// Download `url` into `dest` in `CHUNK_SIZE` ranged requests, trying each chunk up to
+/// `MAX_CHUNK_RETRIES` times in all before giving up — so a connection drop partway through a large
+/// artifact resumes from the last complete chunk instead of restarting from byte 0.
+async fn fetch_ranged(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    total_len: u64,
+) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+
+    let mut file = tokio::fs::File::create(dest)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+
+    let mut offset = 0u64;
+    while offset < total_len {
+        let end = (offset + CHUNK_SIZE - 1).min(total_len - 1);
+        let mut attempt = 0u32;
+        loop {
+            let req = with_gateway_auth(
+                client
+                    .get(url)
+                    .header("Range", format!("bytes={}-{}", offset, end))
+                    .timeout(std::time::Duration::from_secs(30)),
+            );
+            match req.send().await {
+                Ok(resp) if resp.status().is_success() => match resp.bytes().await {
+                    Ok(chunk) => {
+                        tokio::io::AsyncWriteExt::write_all(&mut file, &chunk)
+                            .await
+                            .map_err(|e| format!("Write chunk failed: {}", e))?;
+                        break;
+                    }
+                    Err(e) => {
+                        attempt += 1;
+                        if attempt >= MAX_CHUNK_RETRIES {
+                            return Err(format!("Chunk {}-{} failed after {} attempts: {}", offset, end, attempt, e));
+                        }
+                        log::warn!("Artifact chunk {}-{} read failed ({}), retrying", offset, end, e);
+                    }
+                },
+                Ok(resp) => {
+                    attempt += 1;
+                    if attempt >= MAX_CHUNK_RETRIES {
+                        return Err(format!(
+                            "Gateway returned {} fetching bytes {}-{} of {}",
+                            resp.status(), offset, end, url
+                        ));
+                    }
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt >= MAX_CHUNK_RETRIES {
+                        return Err(format!("Chunk {}-{} failed after {} attempts: {}", offset, end, attempt, e));
+                    }
+                    log::warn!("Artifact chunk {}-{} failed ({}), retrying", offset, end, e);
+                }
+            }
+        }
+        offset = end + 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! # Tamper-Evident Local Action Audit Log
+//!
+//! The Gateway can ask the Companion to run shell commands, write files, and kill processes on
+//! the user's machine. This module records one row per action request in a local SQLite
+//! database — written *before* dispatch (the intent) and updated with the result afterward, so a
+//! crash mid-execution still leaves a visible, un-acknowledged row instead of silence. Each row's
+//! intent fields are chained into the previous row's hash (`prev_hash`/`hash`), so deleting or
+//! editing a row's intent fields breaks the chain for everything after it; recomputing each
+//! row's hash with `chain_hash` exposes the break. This module has no verification pass of its own.
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// One row of the audit log, as returned to callers (e.g. the Tauri commands that list/export it).
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub request_id: String,
+    pub action: String,
+    pub params: String,
+    /// `None` until the action finishes — a row stuck at `None` means it was interrupted.
+    pub success: Option<bool>,
+    pub output: String,
+    pub timestamp: String,
+    pub companion_id: String,
+}
+
+/// Output is truncated to this many bytes before being stored — matches the Gateway's own
+/// action-result truncation, so the audit log never grows unbounded from one chatty command.
+const MAX_STORED_OUTPUT_BYTES: usize = 16 * 1024;
+
+fn db_path() -> Option<std::path::PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("forgeai-companion").join("audit-log.sqlite3"))
+}
+
+fn open_db() -> Result<Connection, String> {
+    let path = db_path().ok_or("Could not resolve local data directory for audit log")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create audit log dir: {}", e))?;
+    }
+    let conn = Connection::open(&path).map_err(|e| format!("Failed to open audit log: {}", e))?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            params TEXT NOT NULL,
+            success INTEGER,
+            output TEXT NOT NULL DEFAULT '',
+            timestamp TEXT NOT NULL,
+            companion_id TEXT NOT NULL,
+            prev_hash TEXT NOT NULL,
+            hash TEXT NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create audit_log table: {}", e))?;
+    Ok(conn)
+}
+
+/// SHA-256 hex digest chaining this row's intent fields onto the previous row's hash. Only the
+/// intent (everything known before dispatch) is chained — the result fields are filled in later
+/// by `record_result` and aren't re-hashed, so the chain proves an action was dispatched, in
+/// order, even if the device loses power before the result comes back.
+fn chain_hash(prev_hash: &str, request_id: &str, action: &str, params: &str, timestamp: &str, companion_id: &str) -> String {
+    use sha2::Digest as _;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(request_id.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(action.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(params.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(timestamp.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(companion_id.as_bytes());
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash of an empty string — the `prev_hash` of the very first row in the chain.
+const GENESIS_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Current UTC time as an RFC 3339 string (e.g. `2026-07-27T12:34:56.789012345+00:00`) — matches the
+/// timestamp format `wake_word::WakeWordEvent` already uses, so every timestamp surfaced to the
+/// user or exported from the Companion reads the same way.
+fn rfc3339_now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Record an action's *intent* before it's dispatched. Returns the row id to pass to
+/// `record_result` once the action finishes. This is a blocking (SQLite) call — callers on an
+/// async task should run it via `tokio::task::spawn_blocking`.
+pub fn record_intent(request_id: &str, action: &str, params: &serde_json::Value, companion_id: &str) -> Result<i64, String> {
+    let conn = open_db()?;
+    let prev_hash: String = conn
+        .query_row("SELECT hash FROM audit_log ORDER BY id DESC LIMIT 1", [], |row| row.get(0))
+        .unwrap_or_else(|_| GENESIS_HASH.to_string());
+
+    let params_json = serde_json::to_string(params).unwrap_or_else(|_| "{}".to_string());
+    let timestamp = rfc3339_now();
+    let hash = chain_hash(&prev_hash, request_id, action, &params_json, &timestamp, companion_id);
+
+    conn.execute(
+        "INSERT INTO audit_log (request_id, action, params, success, output, timestamp, companion_id, prev_hash, hash)
+         VALUES (?1, ?2, ?3, NULL, '', ?4, ?5, ?6, ?7)",
+        rusqlite::params![request_id, action, params_json, timestamp, companion_id, prev_hash, hash],
+    )
+    .map_err(|e| format!("Failed to write audit intent row: {}", e))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Largest byte offset `<= idx` that lands on a UTF-8 char boundary in `s` — lets us truncate
+/// arbitrary action output at a fixed byte budget without risking a mid-character panic.
+fn floor_char_boundary(s: &str, mut idx: usize) -> usize {
+    if idx >= s.len() {
+        return s.len();
+    }
+    while idx > 0 && !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// Fill in an intent row's result once the action finishes.
+pub fn record_result(row_id: i64, success: bool, output: &str) -> Result<(), String> {
+    let conn = open_db()?;
+    let truncated: String = if output.len() > MAX_STORED_OUTPUT_BYTES {
+        let cut = floor_char_boundary(output, MAX_STORED_OUTPUT_BYTES);
+        format!("{}...[truncated]", &output[..cut])
+    } else {
+        output.to_string()
+    };
+    conn.execute(
+        "UPDATE audit_log SET success = ?1, output = ?2 WHERE id = ?3",
+        rusqlite::params![success, truncated, row_id],
+    )
+    .map_err(|e| format!("Failed to write audit result row: {}", e))?;
+    Ok(())
+}
+
+/// Return up to `limit` most recent rows, newest first.
+pub fn query_log(limit: u32) -> Result<Vec<AuditEntry>, String> {
+    let conn = open_db()?;
+    let mut stmt = conn
+        .prepare("SELECT id, request_id, action, params, success, output, timestamp, companion_id FROM audit_log ORDER BY id DESC LIMIT ?1")
+        .map_err(|e| format!("Failed to prepare audit query: {}", e))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![limit], |row| {
+            Ok(AuditEntry {
+                id: row.get(0)?,
+                request_id: row.get(1)?,
+                action: row.get(2)?,
+                params: row.get(3)?,
+                success: row.get::<_, Option<bool>>(4)?,
+                output: row.get(5)?,
+                timestamp: row.get(6)?,
+                companion_id: row.get(7)?,
+            })
+        })
+        .map_err(|e| format!("Failed to run audit query: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read audit rows: {}", e))
+}
+
+/// Export the full audit log as a JSON array.
+pub fn export_json() -> Result<String, String> {
+    let entries = query_log(u32::MAX)?;
+    serde_json::to_string_pretty(&entries).map_err(|e| format!("Failed to serialize audit log: {}", e))
+}
+
+/// Export the full audit log as CSV. Fields are double-quoted with `"` escaped as `""` per the
+/// usual CSV convention, since `output`/`params` can contain commas, quotes, or newlines.
+pub fn export_csv() -> Result<String, String> {
+    let entries = query_log(u32::MAX)?;
+    let mut csv = String::from("id,request_id,action,params,success,output,timestamp,companion_id\n");
+    let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+    for e in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            e.id,
+            quote(&e.request_id),
+            quote(&e.action),
+            quote(&e.params),
+            e.success.map(|s| s.to_string()).unwrap_or_default(),
+            quote(&e.output),
+            quote(&e.timestamp),
+            quote(&e.companion_id),
+        ));
+    }
+    Ok(csv)
+}
@@ -4,18 +4,91 @@
 //! Every command that performs a local action goes through the safety system.
 
 use base64::Engine as _;
-use crate::local_actions::{self, ActionRequest, ActionResult};
+use crate::local_actions::{self, ActionRequest, ActionResult, Pipeline, PipelineResult};
+use crate::rate_limit;
 use crate::safety;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::OnceLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use tokio::sync::Notify;
 
-static GATEWAY_WS_ACTIVE: AtomicBool = AtomicBool::new(false);
-static RECONNECT_NOTIFY: OnceLock<Notify> = OnceLock::new();
+/// One entry per Gateway this companion is currently connected (or connecting) to, keyed by
+/// `companion_id`. Replaces the old single `GATEWAY_WS_ACTIVE`/`RECONNECT_NOTIFY` globals so a
+/// companion can run more than one Gateway connection at once (e.g. a personal and a work
+/// instance), each with its own reconnect signal and outbound queue.
+struct ConnectionHandle {
+    reconnect_notify: Arc<Notify>,
+    /// The live connection's outbound sender, refreshed by `gateway_ws_loop` on every successful
+    /// (re)connect, so other commands (e.g. the voice stream handshake) can push frames onto this
+    /// Gateway's WS without needing their own connection.
+    outbound_tx: Mutex<Option<tokio::sync::mpsc::UnboundedSender<String>>>,
+    /// Broadcasts this Gateway's `connection::ConnectionState` as `gateway_ws_loop` moves through
+    /// it, so any number of subscribers (the frontend bridge in `set_connection_state`, or future
+    /// backend consumers) can observe state changes without polling `CompanionStatus`.
+    state_tx: tokio::sync::watch::Sender<crate::connection::ConnectionState>,
+    /// When the current `state_tx` value was entered — `set_connection_state` uses this to
+    /// attribute elapsed time to the outgoing state before switching to the new one.
+    last_state_change: Mutex<std::time::Instant>,
+}
 
-fn get_reconnect_notify() -> &'static Notify {
-    RECONNECT_NOTIFY.get_or_init(|| Notify::new())
+/// Collapse a `ConnectionState` to the stable label `metrics::record_connection_state_duration`
+/// buckets by, so a changing `Error(..)` message doesn't fragment the exported counters.
+fn connection_state_label(state: &crate::connection::ConnectionState) -> &'static str {
+    match state {
+        crate::connection::ConnectionState::Disconnected => "Disconnected",
+        crate::connection::ConnectionState::Connecting => "Connecting",
+        crate::connection::ConnectionState::Connected => "Connected",
+        crate::connection::ConnectionState::Authenticated => "Authenticated",
+        crate::connection::ConnectionState::Reconnecting => "Reconnecting",
+        crate::connection::ConnectionState::Error(_) => "Error",
+    }
+}
+
+/// Subscribe to one paired Gateway's live connection-state updates. `None` if that Gateway has no
+/// running WS loop (never connected, or already disconnected and dropped from the registry).
+pub fn subscribe_connection_state(
+    companion_id: &str,
+) -> Option<tokio::sync::watch::Receiver<crate::connection::ConnectionState>> {
+    gateway_registry()
+        .lock()
+        .unwrap()
+        .get(companion_id)
+        .map(|handle| handle.state_tx.subscribe())
+}
+
+/// Update one Gateway's observable connection state and forward the change to the frontend, so
+/// the UI learns about a drop or reconnect the moment it happens instead of polling `get_status`.
+fn set_connection_state(app_handle: &tauri::AppHandle, companion_id: &str, state: crate::connection::ConnectionState) {
+    use tauri::Emitter;
+    if let Some(handle) = gateway_registry().lock().unwrap().get(companion_id) {
+        let prev_label = connection_state_label(&handle.state_tx.borrow());
+        let mut last_change = handle.last_state_change.lock().unwrap();
+        crate::metrics::record_connection_state_duration(prev_label, last_change.elapsed().as_secs());
+        *last_change = std::time::Instant::now();
+        let _ = handle.state_tx.send(state.clone());
+    }
+    let _ = app_handle.emit(
+        "gateway-state-changed",
+        serde_json::json!({ "companionId": companion_id, "state": state }),
+    );
+}
+
+static GATEWAY_REGISTRY: OnceLock<Mutex<HashMap<String, ConnectionHandle>>> = OnceLock::new();
+
+fn gateway_registry() -> &'static Mutex<HashMap<String, ConnectionHandle>> {
+    GATEWAY_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Send a JSON frame over a specific paired Gateway's WS connection, if it's currently up.
+fn send_to_gateway(companion_id: &str, json: String) -> Result<(), String> {
+    let registry = gateway_registry().lock().unwrap();
+    let handle = registry.get(companion_id).ok_or("Gateway WS is not connected")?;
+    let tx = handle.outbound_tx.lock().unwrap();
+    tx.as_ref()
+        .ok_or("Gateway WS is not connected")?
+        .send(json)
+        .map_err(|e| format!("Send failed: {}", e))
 }
 
 /// Build a reqwest::RequestBuilder with auth cookie if available
@@ -36,6 +109,9 @@ pub struct CompanionStatus {
     pub auth_token: Option<String>,
     pub safety_active: bool,
     pub version: String,
+    /// Current per-category rate-limit/concurrency state (shared across all connections), so the
+    /// frontend can show when inbound actions are being throttled.
+    pub throttles: Vec<rate_limit::CategoryStatus>,
 }
 
 /// Pairing request from frontend
@@ -58,6 +134,56 @@ pub fn execute_action(request: ActionRequest) -> ActionResult {
     result
 }
 
+/// Like `execute_action`, but for `action: "shell"` emits live output as it arrives instead of
+/// waiting for the whole command to finish. Emits `action-chunk` events to the React frontend
+/// the same way `chat_voice` emits `voice-state`; non-shell actions fall back to `execute_action`.
+#[tauri::command]
+pub fn execute_action_streaming(app_handle: tauri::AppHandle, request: ActionRequest) -> ActionResult {
+    use tauri::Emitter;
+
+    if request.action != "shell" {
+        return execute_action(request);
+    }
+
+    log::info!("Executing streaming action: shell (confirmed: {})", request.confirmed);
+    let mut seq: u64 = 0;
+    let (result, exit_code) = local_actions::run_shell_streaming(&request, &mut |stream, line| {
+        seq += 1;
+        let _ = app_handle.emit("action-chunk", serde_json::json!({
+            "stream": stream,
+            "data": line,
+            "seq": seq,
+        }));
+    });
+    log::info!(
+        "Streaming action result: success={}, risk={:?}, exit_code={:?}",
+        result.success,
+        result.safety.risk,
+        exit_code
+    );
+    result
+}
+
+/// Execute a reviewed multi-step action plan atomically, stopping at the first failed
+/// or (unless pre-confirmed) confirmation-pending step
+#[tauri::command]
+pub fn execute_pipeline(pipeline: Pipeline) -> PipelineResult {
+    log::info!(
+        "Executing pipeline: {} ({} steps, confirmed: {})",
+        pipeline.name.as_deref().unwrap_or("unnamed"),
+        pipeline.steps.len(),
+        pipeline.confirmed
+    );
+    let result = local_actions::execute_pipeline(&pipeline);
+    log::info!(
+        "Pipeline result: success={}, stopped_at={:?}, needs_confirmation={}",
+        result.success,
+        result.stopped_at,
+        result.needs_confirmation
+    );
+    result
+}
+
 /// Check if an action is safe without executing it
 #[tauri::command]
 pub fn check_safety(action: String, path: Option<String>, command: Option<String>) -> safety::SafetyVerdict {
@@ -65,13 +191,14 @@ pub fn check_safety(action: String, path: Option<String>, command: Option<String
         return safety::check_shell_command(cmd);
     }
     if let Some(p) = &path {
-        return safety::check_file_operation(&action, p);
+        return safety::check_file_operation(&action, p, None);
     }
     safety::SafetyVerdict {
         allowed: true,
         risk: safety::RiskLevel::Safe,
         reason: "No path or command to check".into(),
         requires_confirmation: false,
+        requires_elevation: false,
     }
 }
 
@@ -81,18 +208,23 @@ pub fn get_safety_prompt() -> String {
     safety::get_safety_system_prompt()
 }
 
-/// Get companion status
+/// Get the status of every paired Gateway (one companion can now run more than one connection).
 #[tauri::command]
-pub fn get_status() -> CompanionStatus {
-    let creds = crate::connection::GatewayConnection::load_credentials();
-    CompanionStatus {
-        connected: creds.is_some(),
-        gateway_url: creds.as_ref().map(|c| c.gateway_url.clone()),
-        companion_id: creds.as_ref().map(|c| c.companion_id.clone()),
-        auth_token: creds.as_ref().and_then(|c| c.auth_token.clone()),
-        safety_active: true,
-        version: env!("CARGO_PKG_VERSION").to_string(),
-    }
+pub fn get_status() -> Vec<CompanionStatus> {
+    let active = gateway_registry().lock().unwrap();
+    let throttles = rate_limit::snapshot();
+    crate::connection::GatewayConnection::load_all_credentials()
+        .into_iter()
+        .map(|creds| CompanionStatus {
+            connected: active.contains_key(&creds.companion_id),
+            gateway_url: Some(creds.gateway_url),
+            companion_id: Some(creds.companion_id.clone()),
+            auth_token: creds.auth_token,
+            safety_active: true,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            throttles: throttles.clone(),
+        })
+        .collect()
 }
 
 /// Pair with a ForgeAI Gateway by redeeming a pairing code
@@ -100,7 +232,7 @@ pub fn get_status() -> CompanionStatus {
 pub async fn pair_with_gateway(gateway_url: String, pairing_code: String) -> Result<String, String> {
     let url = format!("{}/api/companion/pair", gateway_url.trim_end_matches('/'));
 
-    let client = reqwest::Client::new();
+    let client = crate::tls::http_client(None)?;
     let resp = client
         .post(&url)
         .json(&serde_json::json!({
@@ -144,6 +276,7 @@ pub async fn pair_with_gateway(gateway_url: String, pairing_code: String) -> Res
         companion_id,
         role,
         auth_token,
+        pinned_cert_sha256: None,
     };
 
     crate::connection::GatewayConnection::save_credentials(&creds)?;
@@ -193,6 +326,7 @@ pub async fn chat_send(message: String, session_id: Option<String>) -> Result<se
     // No total timeout — Gateway sends heartbeat spaces every 10s to keep alive.
     // Only connect_timeout to fail fast if server is unreachable.
     let client = reqwest::Client::builder()
+        .use_preconfigured_tls(crate::tls::client_config(Some(&creds))?)
         .connect_timeout(std::time::Duration::from_secs(15))
         .build()
         .map_err(|e| format!("HTTP client error: {}", e))?;
@@ -292,6 +426,7 @@ pub async fn chat_voice(
     // Retry once on connection errors (server may be busy with agent tools)
     let url = format!("{}/api/chat/voice", creds.gateway_url);
     let client = reqwest::Client::builder()
+        .use_preconfigured_tls(crate::tls::client_config(Some(&creds))?)
         .timeout(std::time::Duration::from_secs(180))
         .build()
         .map_err(|e| format!("HTTP client error: {}", e))?;
@@ -390,54 +525,256 @@ pub fn disconnect() -> Result<String, String> {
 
 // ─── Gateway WebSocket Background Loop ──────────────────────────────
 
-/// Spawn the persistent Gateway WebSocket loop (idempotent — only one loop runs)
-pub fn spawn_gateway_ws() {
-    if GATEWAY_WS_ACTIVE.swap(true, Ordering::SeqCst) {
-        log::info!("[GatewayWS] Loop already active");
+/// Spawn a Gateway WS loop for every paired Gateway that doesn't already have one running.
+/// Idempotent per `companion_id`. Needs an `AppHandle` so action approval prompts can be emitted
+/// to the frontend.
+pub fn spawn_gateway_ws(app_handle: tauri::AppHandle) {
+    for creds in crate::connection::GatewayConnection::load_all_credentials() {
+        spawn_gateway_connection(app_handle.clone(), creds.companion_id);
+    }
+}
+
+/// Start (if not already running) the WS loop for one specific paired Gateway.
+fn spawn_gateway_connection(app_handle: tauri::AppHandle, companion_id: String) {
+    let mut registry = gateway_registry().lock().unwrap();
+    if registry.contains_key(&companion_id) {
+        log::info!("[GatewayWS] Loop already active for {}", companion_id);
         return;
     }
-    tauri::async_runtime::spawn(async {
-        gateway_ws_loop().await;
-        GATEWAY_WS_ACTIVE.store(false, Ordering::SeqCst);
+    let reconnect_notify = Arc::new(Notify::new());
+    let (state_tx, _) = tokio::sync::watch::channel(crate::connection::ConnectionState::Disconnected);
+    registry.insert(companion_id.clone(), ConnectionHandle {
+        reconnect_notify: reconnect_notify.clone(),
+        outbound_tx: Mutex::new(None),
+        state_tx,
+        last_state_change: Mutex::new(std::time::Instant::now()),
+    });
+    drop(registry);
+
+    tauri::async_runtime::spawn(async move {
+        gateway_ws_loop(app_handle, companion_id.clone(), reconnect_notify).await;
+        gateway_registry().lock().unwrap().remove(&companion_id);
     });
 }
 
-/// Tauri command: ensure the Gateway WS is running (called after pairing)
+/// Tauri command: ensure every paired Gateway has a running WS connection (called after pairing)
 #[tauri::command]
-pub async fn connect_gateway_ws() -> Result<String, String> {
-    spawn_gateway_ws();
-    Ok("Gateway WS connection started".into())
+pub async fn connect_gateway_ws(app_handle: tauri::AppHandle) -> Result<String, String> {
+    spawn_gateway_ws(app_handle);
+    Ok("Gateway WS connection(s) started".into())
 }
 
-/// Tauri command: force the WS loop to reconnect with fresh credentials (call after re-pairing)
+/// Tauri command: force one Gateway's WS loop to reconnect with fresh credentials (call after
+/// re-pairing that Gateway).
 #[tauri::command]
-pub async fn force_reconnect_gateway_ws() -> Result<String, String> {
-    log::info!("[GatewayWS] Force reconnect requested");
-    get_reconnect_notify().notify_one();
-    // Wait for old loop to exit, then start fresh
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-    GATEWAY_WS_ACTIVE.store(false, Ordering::SeqCst);
-    spawn_gateway_ws();
+pub async fn force_reconnect_gateway_ws(app_handle: tauri::AppHandle, companion_id: String) -> Result<String, String> {
+    log::info!("[GatewayWS] Force reconnect requested for {}", companion_id);
+    let running = match gateway_registry().lock().unwrap().get(&companion_id) {
+        Some(handle) => {
+            handle.reconnect_notify.notify_one();
+            true
+        }
+        None => false,
+    };
+    // A running loop drops its connection on this signal, reloads credentials, and reconnects
+    // without backoff; only start a loop if this Gateway has none.
+    if !running {
+        spawn_gateway_connection(app_handle, companion_id);
+    }
     Ok("Reconnect initiated".into())
 }
 
-async fn gateway_ws_loop() {
+/// Sequence-numbered outbound `action_result`s awaiting a Gateway ack.
+///
+/// Lives for the whole lifetime of `gateway_ws_loop`, outliving any single WebSocket
+/// connection, so a result produced mid-reconnect is redelivered once the next connection
+/// comes up instead of being dropped with the old `tx`/`rx` channel.
+struct OutboundQueue {
+    next_seq: AtomicU64,
+    last_acked: AtomicU64,
+    unacked: std::sync::Mutex<std::collections::VecDeque<(u64, String)>>,
+}
+
+impl OutboundQueue {
+    /// Cap on buffered un-acked results — protects memory if the Gateway is down for a long time.
+    const MAX_BUFFERED: usize = 256;
+
+    fn new() -> Self {
+        Self {
+            next_seq: AtomicU64::new(1),
+            last_acked: AtomicU64::new(0),
+            unacked: std::sync::Mutex::new(std::collections::VecDeque::new()),
+        }
+    }
+
+    /// Stamp `value` with the next `seq` and buffer it until the Gateway acks it.
+    fn enqueue_result(&self, mut value: serde_json::Value) -> String {
+        let seq = self.next_seq.fetch_add(1, Ordering::SeqCst);
+        value["seq"] = serde_json::json!(seq);
+        let json = value.to_string();
+
+        let mut unacked = self.unacked.lock().unwrap();
+        unacked.push_back((seq, json.clone()));
+        while unacked.len() > Self::MAX_BUFFERED {
+            unacked.pop_front();
+        }
+        json
+    }
+
+    /// The Gateway has confirmed receipt through `seq` — forget everything up to it.
+    fn ack(&self, seq: u64) {
+        self.last_acked.store(seq, Ordering::SeqCst);
+        self.unacked.lock().unwrap().retain(|(s, _)| *s > seq);
+    }
+
+    fn last_acked(&self) -> u64 {
+        self.last_acked.load(Ordering::SeqCst)
+    }
+
+    /// Everything still waiting on an ack, oldest first — resent after every reconnect.
+    fn unacked_snapshot(&self) -> Vec<String> {
+        self.unacked.lock().unwrap().iter().map(|(_, json)| json.clone()).collect()
+    }
+}
+
+/// How a user responded to an `action-approval-request` event, via `respond_to_action_approval`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ApprovalDecision {
+    Approved,
+    Denied,
+}
+
+/// Outcome of gating a Gateway-initiated action behind human approval. `Canceled` covers both
+/// the auto-deny timeout and the approval channel being dropped (e.g. app shutdown).
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ApprovalOutcome {
+    Approved,
+    Denied,
+    Canceled,
+}
+
+/// How long an `action-approval-request` waits for a response before auto-denying.
+const APPROVAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+static PENDING_APPROVALS: OnceLock<
+    std::sync::Mutex<std::collections::HashMap<String, std::sync::mpsc::Sender<ApprovalDecision>>>,
+> = OnceLock::new();
+
+fn pending_approvals(
+) -> &'static std::sync::Mutex<std::collections::HashMap<String, std::sync::mpsc::Sender<ApprovalDecision>>> {
+    PENDING_APPROVALS.get_or_init(|| std::sync::Mutex::new(std::collections::HashMap::new()))
+}
+
+/// Tauri command: the frontend's response to an `action-approval-request` event.
+#[tauri::command]
+pub fn respond_to_action_approval(request_id: String, decision: ApprovalDecision) -> Result<(), String> {
+    let pending = pending_approvals().lock().unwrap();
+    match pending.get(&request_id) {
+        Some(tx) => tx
+            .send(decision)
+            .map_err(|e| format!("Approval channel closed: {}", e)),
+        None => Err(format!("No pending approval for request {}", request_id)),
+    }
+}
+
+/// Suspend a Gateway-initiated action that `safety` flagged as `requires_confirmation` until
+/// the user approves or denies it from the frontend, or `APPROVAL_TIMEOUT` elapses.
+fn request_approval(
+    app_handle: &tauri::AppHandle,
+    request_id: &str,
+    action: &str,
+    verdict: &safety::SafetyVerdict,
+) -> ApprovalOutcome {
+    use tauri::Emitter;
+
+    let (tx, rx) = std::sync::mpsc::channel::<ApprovalDecision>();
+    pending_approvals().lock().unwrap().insert(request_id.to_string(), tx);
+
+    let _ = app_handle.emit(
+        "action-approval-request",
+        serde_json::json!({
+            "requestId": request_id,
+            "action": action,
+            "risk": verdict.risk,
+            "reason": verdict.reason,
+        }),
+    );
+    log::info!("[GatewayWS] Awaiting approval for {} (id={})", action, request_id);
+
+    let outcome = match rx.recv_timeout(APPROVAL_TIMEOUT) {
+        Ok(ApprovalDecision::Approved) => ApprovalOutcome::Approved,
+        Ok(ApprovalDecision::Denied) => ApprovalOutcome::Denied,
+        Err(_) => {
+            log::warn!("[GatewayWS] Approval for {} timed out, auto-denying", request_id);
+            ApprovalOutcome::Canceled
+        }
+    };
+
+    pending_approvals().lock().unwrap().remove(request_id);
+    outcome
+}
+
+/// Per-Gateway connection bookkeeping that outlives any single WebSocket connection, so the
+/// keepalive/send tasks and the reconnect/resume logic can all read it without taking a lock on
+/// the hot path. `last_seq` is the highest event sequence number seen *from* the Gateway — used
+/// to ask it to replay only what we missed instead of restarting the whole event stream.
+struct ConnectionState {
+    attempt: AtomicU32,
+    last_seq: AtomicU64,
+    session_id: Mutex<Option<String>>,
+}
+
+impl ConnectionState {
+    fn new() -> Self {
+        Self {
+            attempt: AtomicU32::new(0),
+            last_seq: AtomicU64::new(0),
+            session_id: Mutex::new(None),
+        }
+    }
+}
+
+/// Runs the WS connection for exactly one paired Gateway (`companion_id`), reconnecting with
+/// backoff until its credentials are removed (disconnect/re-pair), at which point it returns and
+/// `spawn_gateway_connection`'s caller drops it from the registry. A heartbeat watchdog drops and
+/// reconnects a half-open connection that's gone quiet for longer than
+/// `ConnectionConfig::heartbeat_timeout_secs`, since a dead TCP socket otherwise delivers no read
+/// error until the OS eventually tears it down. Every state transition is published through
+/// `set_connection_state` so subscribers (the frontend, via `gateway-state-changed`) see a
+/// connection drop or recover without polling `get_status`.
+async fn gateway_ws_loop(app_handle: tauri::AppHandle, companion_id: String, reconnect_notify: Arc<Notify>) {
     use futures_util::{SinkExt, StreamExt};
-    use tokio_tungstenite::{connect_async, tungstenite::Message};
+    use tauri::Manager;
+    use tokio_tungstenite::{connect_async_tls_with_config, tungstenite::Message};
 
     // Brief delay so the app is fully initialized
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
+    const BACKOFF_BASE: std::time::Duration = std::time::Duration::from_secs(1);
+    const BACKOFF_CAP: std::time::Duration = std::time::Duration::from_secs(60);
+    const STABLE_AFTER: std::time::Duration = std::time::Duration::from_secs(30);
+
+    // Outlives every individual WebSocket connection so the reconnect attempt count, last-seen
+    // event sequence, and resumable session id all survive a reconnect.
+    let conn_state = ConnectionState::new();
+
+    // Outlives every individual WebSocket connection so un-acked results survive a reconnect.
+    let outbound = std::sync::Arc::new(OutboundQueue::new());
+
     loop {
-        // Reload credentials each iteration (handles re-pairing)
-        let creds = match crate::connection::GatewayConnection::load_credentials() {
+        // Reload credentials each iteration (handles re-pairing). Once this Gateway has been
+        // disconnected its credentials are gone for good, so exit instead of retrying forever.
+        let creds = match crate::connection::GatewayConnection::load_credentials_for(&companion_id) {
             Some(c) => c,
             None => {
-                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                continue;
+                log::info!("[GatewayWS] No credentials for {}, stopping loop", companion_id);
+                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Disconnected);
+                return;
             }
         };
 
+        set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Connecting);
+
         // Build WS URL with companionId + auth token
         let ws_base = creds.gateway_url
             .replace("https://", "wss://")
@@ -449,12 +786,51 @@ async fn gateway_ws_loop() {
 
         log::info!("[GatewayWS] Connecting: companionId={}", creds.companion_id);
 
-        match connect_async(&ws_url).await {
+        let mut manual_reconnect = false;
+
+        let connector = match crate::tls::ws_connector(Some(&creds)) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("[GatewayWS] TLS setup failed: {}", e);
+                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Error(e.to_string()));
+                let delay = reconnect_delay(conn_state.attempt.load(Ordering::SeqCst), BACKOFF_BASE, BACKOFF_CAP);
+                conn_state.attempt.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+
+        match connect_async_tls_with_config(&ws_url, None, false, Some(connector)).await {
             Ok((ws_stream, _)) => {
                 log::info!("[GatewayWS] Connected to {}", creds.gateway_url);
+                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Connected);
+                let connected_at = std::time::Instant::now();
                 let (mut write, mut read) = ws_stream.split();
                 let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
 
+                // Publish this connection's sender so other commands (e.g. the voice stream
+                // handshake) can push frames onto this Gateway's WS.
+                if let Some(handle) = gateway_registry().lock().unwrap().get(&companion_id) {
+                    *handle.outbound_tx.lock().unwrap() = Some(tx.clone());
+                }
+
+                // Resume handshake: identify which session we're resuming and what we last saw
+                // (both our own acked results and the Gateway's event stream), then redeliver any
+                // action_result it never confirmed from the previous connection. If the Gateway
+                // doesn't recognize the session it replies with `resume_rejected` and we fall back
+                // to a fresh stream from this same (already-authenticated) connection.
+                let resume = serde_json::json!({
+                    "type": "resume",
+                    "companionId": creds.companion_id,
+                    "sessionId": conn_state.session_id.lock().unwrap().clone(),
+                    "lastSeq": conn_state.last_seq.load(Ordering::SeqCst),
+                    "lastAckedSeq": outbound.last_acked(),
+                }).to_string();
+                let _ = tx.send(resume);
+                for buffered in outbound.unacked_snapshot() {
+                    let _ = tx.send(buffered);
+                }
+
                 // Send task: forwards outgoing messages to WS
                 let send_handle = tokio::spawn(async move {
                     while let Some(msg) = rx.recv().await {
@@ -465,15 +841,26 @@ async fn gateway_ws_loop() {
                     }
                 });
 
-                // Keepalive ping interval (30s)
-                let mut ping_interval = tokio::time::interval(std::time::Duration::from_secs(30));
+                // Keepalive ping interval — defaults match the Gateway's own advised cadence (see
+                // `connection::ConnectionConfig`).
+                let heartbeat = crate::connection::ConnectionConfig::default();
+                let mut ping_interval = tokio::time::interval(std::time::Duration::from_secs(heartbeat.heartbeat_interval_secs));
                 ping_interval.tick().await; // consume initial tick
 
+                // Dead-connection watchdog: a half-open TCP connection delivers no read error
+                // until the OS eventually tears it down, so track the last time *any* frame (not
+                // just a pong) arrived and drop the connection ourselves if nothing's arrived
+                // within `heartbeat_timeout_secs` — the reconnect loop picks it up from there.
+                let mut last_activity = std::time::Instant::now();
+                let mut watchdog_interval = tokio::time::interval(std::time::Duration::from_secs(5));
+                watchdog_interval.tick().await; // consume initial tick
+
                 // Receive loop with keepalive
                 let mut alive = true;
                 while alive {
                     tokio::select! {
                         msg = read.next() => {
+                            last_activity = std::time::Instant::now();
                             match msg {
                                 Some(Ok(Message::Text(text))) => {
                                     let text_str: String = text.to_string();
@@ -486,6 +873,12 @@ async fn gateway_ws_loop() {
                                     };
                                     let msg_type = raw.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
+                                    // Track the highest event seq the Gateway has sent us, regardless
+                                    // of frame type, so a future resume only asks for what's missing.
+                                    if let Some(seq) = raw.get("seq").and_then(|v| v.as_u64()) {
+                                        conn_state.last_seq.fetch_max(seq, Ordering::SeqCst);
+                                    }
+
                                     match msg_type {
                                         "action_request" => {
                                             let request_id = raw.get("requestId")
@@ -497,16 +890,77 @@ async fn gateway_ws_loop() {
 
                                             log::info!("[GatewayWS] >>> Action request: {} (id={})", action, request_id);
 
+                                            // Throttle before doing any work — a saturated bucket or
+                                            // concurrency cap rejects immediately instead of queuing.
+                                            let category = rate_limit::ActionCategory::classify(&action);
+                                            let admission = match rate_limit::try_admit(category) {
+                                                Ok(guard) => guard,
+                                                Err(retry_after_ms) => {
+                                                    log::warn!("[GatewayWS] Rate limited: {} (id={}), retry in {}ms", action, request_id, retry_after_ms);
+                                                    let response = serde_json::json!({
+                                                        "type": "action_result",
+                                                        "requestId": request_id,
+                                                        "status": "rate_limited",
+                                                        "success": false,
+                                                        "output": format!("Rate limited: too many '{}' actions", action),
+                                                        "retryAfterMs": retry_after_ms,
+                                                    });
+                                                    let json = outbound.enqueue_result(response);
+                                                    let _ = tx.send(json);
+                                                    continue;
+                                                }
+                                            };
+
                                             // Execute in a blocking thread so we don't stall the async loop
                                             let action_clone = action.clone();
                                             let tx_clone = tx.clone();
+                                            let outbound_clone = outbound.clone();
                                             let req_id = request_id.clone();
+                                            let app_handle_clone = app_handle.clone();
+                                            let companion_id_clone = companion_id.clone();
                                             tokio::task::spawn_blocking(move || {
+                                                // Held until this closure returns, releasing the category's
+                                                // concurrency slot for the next action.
+                                                let _admission = admission;
+                                                // Audit the intent before anything runs, so an interrupted
+                                                // action still leaves a row behind.
+                                                let audit_row = crate::audit::record_intent(&req_id, &action_clone, &params, &companion_id_clone)
+                                                    .map_err(|e| log::warn!("[GatewayWS] Audit intent write failed: {}", e))
+                                                    .ok();
                                                 // Desktop actions get raw params; others use ActionRequest
+                                                let mut exit_code: Option<i32> = None;
+                                                // "completed" unless an approval gate below denies or times out.
+                                                let mut status = "completed";
+
+                                                let run_now = |action_req: &ActionRequest| -> (ActionResult, Option<i32>) {
+                                                    if action_clone == "shell" {
+                                                        // Stream stdout/stderr as action_chunk frames instead of
+                                                        // waiting for the whole command to finish.
+                                                        let tx_chunk = tx_clone.clone();
+                                                        let req_id_chunk = req_id.clone();
+                                                        let mut chunk_seq: u64 = 0;
+                                                        local_actions::run_shell_streaming(action_req, &mut |stream, line| {
+                                                            chunk_seq += 1;
+                                                            let chunk = serde_json::json!({
+                                                                "type": "action_chunk",
+                                                                "requestId": req_id_chunk,
+                                                                "stream": stream,
+                                                                "data": line,
+                                                                "seq": chunk_seq,
+                                                            });
+                                                            if let Ok(json) = serde_json::to_string(&chunk) {
+                                                                let _ = tx_chunk.send(json);
+                                                            }
+                                                        })
+                                                    } else {
+                                                        (local_actions::execute(action_req), None)
+                                                    }
+                                                };
+
                                                 let result = if action_clone == "desktop" {
                                                     local_actions::execute_desktop(&params)
                                                 } else {
-                                                    let action_req = ActionRequest {
+                                                    let mut action_req = ActionRequest {
                                                         action: action_clone.clone(),
                                                         path: params.get("path").and_then(|v| v.as_str()).map(String::from),
                                                         command: params.get("command").and_then(|v| v.as_str()).map(String::from),
@@ -514,31 +968,116 @@ async fn gateway_ws_loop() {
                                                         process_name: params.get("process_name").and_then(|v| v.as_str()).map(String::from),
                                                         app_name: params.get("app_name").and_then(|v| v.as_str()).map(String::from),
                                                         cwd: params.get("cwd").and_then(|v| v.as_str()).map(String::from),
-                                                        confirmed: true,
+                                                        confirmed: false,
+                                                        atomic: params.get("atomic").and_then(|v| v.as_bool()).unwrap_or(true),
+                                                        backup: params.get("backup").and_then(|v| v.as_bool()).unwrap_or(false),
+                                                        algo: params.get("algo").and_then(|v| v.as_str()).map(String::from),
+                                                        expected_hash: params.get("expected_hash").and_then(|v| v.as_str()).map(String::from),
+                                                        pid: params.get("pid").and_then(|v| v.as_u64()).map(|p| p as u32),
+                                                    };
+
+                                                    // Pre-flight the same safety gate `execute` would apply, so we
+                                                    // can suspend for approval *before* running anything risky.
+                                                    let verdict = match action_clone.as_str() {
+                                                        "shell" => action_req.command.as_deref().map(safety::check_shell_command),
+                                                        "delete_file" => action_req.path.as_deref().map(|p| safety::check_file_operation("delete", p, None)),
+                                                        "kill_process" => action_req.process_name.as_deref().map(safety::check_process_kill),
+                                                        "process_info" => action_req.pid.map(|p| p.to_string()).or_else(|| action_req.process_name.clone())
+                                                            .map(|t| safety::check_process_access(&t, "query_information")),
+                                                        // `execute` never holds any other action for confirmation.
+                                                        _ => None,
                                                     };
-                                                    local_actions::execute(&action_req)
+                                                    let needs_approval = verdict.as_ref().is_some_and(|v| v.requires_confirmation);
+
+                                                    if needs_approval {
+                                                        let verdict = verdict.unwrap();
+                                                        match request_approval(&app_handle_clone, &req_id, &action_clone, &verdict) {
+                                                            ApprovalOutcome::Approved => {
+                                                                action_req.confirmed = true;
+                                                                let (result, code) = run_now(&action_req);
+                                                                exit_code = code;
+                                                                result
+                                                            }
+                                                            ApprovalOutcome::Denied => {
+                                                                status = "denied";
+                                                                ActionResult::err("User denied this action".into(), verdict)
+                                                            }
+                                                            ApprovalOutcome::Canceled => {
+                                                                status = "canceled";
+                                                                ActionResult::err("Approval timed out".into(), verdict)
+                                                            }
+                                                        }
+                                                    } else {
+                                                        action_req.confirmed = true;
+                                                        let (result, code) = run_now(&action_req);
+                                                        exit_code = code;
+                                                        result
+                                                    }
                                                 };
-                                                log::info!("[GatewayWS] <<< Action result: {} success={} output_len={}",
-                                                    action_clone, result.success, result.output.len());
+                                                log::info!("[GatewayWS] <<< Action result: {} status={} success={} output_len={}",
+                                                    action_clone, status, result.success, result.output.len());
+                                                if let Some(row_id) = audit_row {
+                                                    if let Err(e) = crate::audit::record_result(row_id, result.success, &result.output) {
+                                                        log::warn!("[GatewayWS] Audit result write failed: {}", e);
+                                                    }
+                                                }
+                                                crate::metrics::record_action_result(&action_clone, result.success);
 
                                                 let response = serde_json::json!({
                                                     "type": "action_result",
                                                     "requestId": req_id,
+                                                    "status": status,
                                                     "success": result.success,
                                                     "output": result.output,
+                                                    "exitCode": exit_code,
                                                 });
-                                                if let Ok(json) = serde_json::to_string(&response) {
-                                                    if let Err(e) = tx_clone.send(json) {
-                                                        log::error!("[GatewayWS] Failed to queue response: {}", e);
-                                                    } else {
-                                                        log::info!("[GatewayWS] Response queued for {}", req_id);
-                                                    }
+                                                // Buffered by seq so it's redelivered if the
+                                                // Gateway never acks it before we reconnect.
+                                                let json = outbound_clone.enqueue_result(response);
+                                                if let Err(e) = tx_clone.send(json) {
+                                                    log::error!("[GatewayWS] Failed to queue response: {}", e);
+                                                } else {
+                                                    log::info!("[GatewayWS] Response queued for {}", req_id);
                                                 }
                                             });
                                         }
                                         "health.pong" => {
                                             log::debug!("[GatewayWS] Keepalive pong received");
                                         }
+                                        "ack" => {
+                                            if let Some(seq) = raw.get("seq").and_then(|v| v.as_u64()) {
+                                                outbound.ack(seq);
+                                            }
+                                        }
+                                        "session" => {
+                                            if let Some(id) = raw.get("sessionId").and_then(|v| v.as_str()) {
+                                                *conn_state.session_id.lock().unwrap() = Some(id.to_string());
+                                                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Authenticated);
+                                            }
+                                        }
+                                        "auth_error" => {
+                                            log::warn!("[GatewayWS] Gateway reported an auth error, refreshing token");
+                                            match crate::connection::refresh_auth_token(&creds).await {
+                                                Ok(_) => {
+                                                    log::info!("[GatewayWS] Token refreshed, reconnecting immediately");
+                                                    manual_reconnect = true;
+                                                }
+                                                Err(e) => log::error!("[GatewayWS] Token refresh failed: {}", e),
+                                            }
+                                            alive = false;
+                                        }
+                                        "resume_rejected" => {
+                                            log::warn!("[GatewayWS] Gateway rejected resume, starting a fresh event stream");
+                                            *conn_state.session_id.lock().unwrap() = None;
+                                            conn_state.last_seq.store(0, Ordering::SeqCst);
+                                        }
+                                        "voice_ready" => {
+                                            if let Some(req_id) = raw.get("requestId").and_then(|v| v.as_str()) {
+                                                if let Some(sender) = pending_voice_ready().lock().unwrap().remove(req_id) {
+                                                    let _ = sender.send(raw.clone());
+                                                }
+                                            }
+                                        }
                                         _ => {
                                             log::debug!("[GatewayWS] Received: {}", msg_type);
                                         }
@@ -577,25 +1116,178 @@ async fn gateway_ws_loop() {
                                 log::debug!("[GatewayWS] Keepalive ping sent");
                             }
                         }
-                        _ = get_reconnect_notify().notified() => {
+                        _ = watchdog_interval.tick() => {
+                            if last_activity.elapsed() > std::time::Duration::from_secs(heartbeat.heartbeat_timeout_secs) {
+                                log::warn!(
+                                    "[GatewayWS] Heartbeat timeout — no traffic in over {}s, dropping connection",
+                                    heartbeat.heartbeat_timeout_secs
+                                );
+                                alive = false;
+                            }
+                        }
+                        _ = reconnect_notify.notified() => {
                             log::info!("[GatewayWS] Reconnect signal received, closing current connection");
+                            if let Some(voice_state) = app_handle.try_state::<VoiceState>() {
+                                if let Ok(mut engine) = voice_state.0.lock() {
+                                    engine.stop_stream();
+                                }
+                            }
+                            if let Some(process_state) = app_handle.try_state::<ProcessState>() {
+                                if let Ok(registry) = process_state.0.lock() {
+                                    registry.kill_all();
+                                }
+                            }
+                            manual_reconnect = true;
                             alive = false;
                         }
                     }
                 }
 
                 send_handle.abort();
-                log::warn!("[GatewayWS] Disconnected, reconnecting in 5s...");
+                // Reap interactive process sessions whenever the connection ends, not only on a
+                // manual reconnect.
+                if let Some(process_state) = app_handle.try_state::<ProcessState>() {
+                    if let Ok(registry) = process_state.0.lock() {
+                        registry.kill_all();
+                    }
+                }
+                if let Some(handle) = gateway_registry().lock().unwrap().get(&companion_id) {
+                    *handle.outbound_tx.lock().unwrap() = None;
+                }
+
+                // Only a connection that proved itself stable earns back a clean slate —
+                // a connection that flaps (drops before STABLE_AFTER) keeps backing off.
+                if connected_at.elapsed() >= STABLE_AFTER {
+                    conn_state.attempt.store(0, Ordering::SeqCst);
+                }
+                log::warn!("[GatewayWS] Disconnected after {:?}", connected_at.elapsed());
+                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Reconnecting);
             }
             Err(e) => {
-                log::error!("[GatewayWS] Connection failed: {}, retry in 5s...", e);
+                log::error!("[GatewayWS] Connection failed: {}", e);
+                set_connection_state(&app_handle, &companion_id, crate::connection::ConnectionState::Error(e.to_string()));
+                if crate::connection::is_auth_rejection(&e) {
+                    log::warn!("[GatewayWS] Connection rejected (401), refreshing token");
+                    match crate::connection::refresh_auth_token(&creds).await {
+                        Ok(_) => {
+                            log::info!("[GatewayWS] Token refreshed, retrying immediately");
+                            manual_reconnect = true;
+                        }
+                        Err(refresh_err) => log::error!("[GatewayWS] Token refresh failed: {}", refresh_err),
+                    }
+                }
             }
         }
 
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        crate::metrics::record_reconnect_attempt();
+
+        if manual_reconnect {
+            log::info!("[GatewayWS] Manual reconnect — skipping backoff");
+            continue;
+        }
+
+        let attempt = conn_state.attempt.fetch_add(1, Ordering::SeqCst);
+        let delay = reconnect_delay(attempt, BACKOFF_BASE, BACKOFF_CAP);
+        log::info!("[GatewayWS] Reconnecting in {:?} (attempt {})", delay, attempt + 1);
+        tokio::time::sleep(delay).await;
     }
 }
 
+/// Exponential backoff with jitter: `min(cap, base * 2^attempt)` plus up to half that again at
+/// random, so many companions reconnecting to the same Gateway after an outage don't all retry
+/// in lockstep.
+fn reconnect_delay(
+    attempt: u32,
+    base: std::time::Duration,
+    cap: std::time::Duration,
+) -> std::time::Duration {
+    // 2^6 * 1s already exceeds the 60s cap, so higher attempts can't overflow the shift.
+    let factor = 1u32 << attempt.min(6);
+    let delay = base.saturating_mul(factor).min(cap);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let jitter_fraction = (nanos % 1000) as f64 / 1000.0 / 2.0; // [0, 0.5)
+    delay + delay.mul_f64(jitter_fraction)
+}
+
+// ─── Full-Duplex Voice Streaming ──────────────────────────────
+
+/// How long `voice_stream_start` waits for the Gateway's `voice_ready` frame before giving up.
+const VOICE_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+static PENDING_VOICE_READY: OnceLock<Mutex<HashMap<String, tokio::sync::oneshot::Sender<serde_json::Value>>>> = OnceLock::new();
+
+fn pending_voice_ready() -> &'static Mutex<HashMap<String, tokio::sync::oneshot::Sender<serde_json::Value>>> {
+    PENDING_VOICE_READY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Open a full-duplex voice session: send a `voice_identify` handshake over the paired Gateway's
+/// WS (reusing its existing connection — no separate socket needed for signaling), wait for the
+/// `voice_ready` reply carrying the media endpoint, then start streaming Opus frames to it.
+#[tauri::command]
+pub async fn voice_stream_start(app_handle: tauri::AppHandle, state: State<'_, VoiceState>) -> Result<String, String> {
+    use tauri::Emitter;
+
+    let creds = crate::connection::GatewayConnection::load_credentials()
+        .ok_or("Not connected — pair first")?;
+
+    let ssrc: u32 = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(1);
+    let request_id = format!("voice-{}-{}", creds.companion_id, ssrc);
+
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<serde_json::Value>();
+    pending_voice_ready().lock().unwrap().insert(request_id.clone(), ready_tx);
+
+    let identify = serde_json::json!({
+        "type": "voice_identify",
+        "requestId": request_id,
+        "companionId": creds.companion_id,
+        "authToken": creds.auth_token,
+        "codec": "opus",
+        "sampleRate": 48000,
+        "ssrc": ssrc,
+    });
+    if let Err(e) = send_to_gateway(&creds.companion_id, identify.to_string()) {
+        pending_voice_ready().lock().unwrap().remove(&request_id);
+        return Err(e);
+    }
+
+    let _ = app_handle.emit("voice-stream-state", serde_json::json!({ "state": "connecting" }));
+
+    let ready = match tokio::time::timeout(VOICE_READY_TIMEOUT, ready_rx).await {
+        Ok(Ok(frame)) => frame,
+        Ok(Err(_)) => return Err("Voice handshake channel closed".into()),
+        Err(_) => {
+            pending_voice_ready().lock().unwrap().remove(&request_id);
+            return Err("Gateway did not respond to voice handshake".into());
+        }
+    };
+
+    let host = ready.get("host").and_then(|v| v.as_str()).ok_or("Ready frame missing host")?.to_string();
+    let port = ready.get("port").and_then(|v| v.as_u64()).ok_or("Ready frame missing port")? as u16;
+    let secret = ready.get("secret").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.start_stream(app_handle.clone(), host, port, secret, ssrc)?;
+
+    Ok("Voice stream started".into())
+}
+
+/// Tear down the live voice stream, if any.
+#[tauri::command]
+pub fn voice_stream_stop(app_handle: tauri::AppHandle, state: State<'_, VoiceState>) -> Result<String, String> {
+    use tauri::Emitter;
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.stop_stream();
+    let _ = app_handle.emit("voice-stream-state", serde_json::json!({ "state": "closed" }));
+    Ok("Voice stream stopped".into())
+}
+
 /// Get system info (safe, no confirmation needed)
 #[tauri::command]
 pub fn get_system_info() -> ActionResult {
@@ -608,6 +1300,11 @@ pub fn get_system_info() -> ActionResult {
         app_name: None,
         cwd: None,
         confirmed: false,
+        atomic: true,
+        backup: false,
+        algo: None,
+        expected_hash: None,
+        pid: None,
     })
 }
 
@@ -646,11 +1343,24 @@ pub fn wake_word_start(
     state: State<'_, WakeWordState>,
     app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
-    let engine = state.0.lock().map_err(|e| e.to_string())?;
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    // Wire up the first paired Gateway's live outbound sender (same "first paired" convention as
+    // `voice_stream_start`) so a detected voice turn's captured audio actually streams out as
+    // `audio_chunk` messages instead of being captured and thrown away.
+    engine.set_gateway_sender(first_active_outbound_sender());
     engine.start(app_handle)?;
     Ok("Wake word detection started".into())
 }
 
+/// The first paired Gateway's live outbound sender, if its WS is currently up — used to wire a
+/// fresh `WakeWordEngine` to the active connection the same way `send_to_gateway` looks one up by
+/// `companion_id` for a single request.
+fn first_active_outbound_sender() -> Option<tokio::sync::mpsc::UnboundedSender<String>> {
+    let creds = crate::connection::GatewayConnection::load_credentials()?;
+    let registry = gateway_registry().lock().unwrap();
+    registry.get(&creds.companion_id)?.outbound_tx.lock().unwrap().clone()
+}
+
 /// Stop wake word detection
 #[tauri::command]
 pub fn wake_word_stop(state: State<'_, WakeWordState>) -> Result<String, String> {
@@ -668,11 +1378,67 @@ pub fn wake_word_status(state: State<'_, WakeWordState>) -> Result<WakeWordStatu
 
 // ─── Voice Commands ──────────────────────────────────
 
-/// Record audio from microphone (stops on silence or manual stop)
+// ─── Process Commands ─────────────────────────────────
+
+/// Managed state for interactive process sessions
+pub struct ProcessState(pub Mutex<crate::process::ProcessRegistry>);
+
+/// Launch an interactive process under a pseudo-terminal; output streams back as `process-output`
+/// events tagged by the returned id, with a final `process-exit` event on termination.
+#[tauri::command]
+pub fn proc_spawn(
+    app_handle: tauri::AppHandle,
+    state: State<'_, ProcessState>,
+    command: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+) -> Result<u64, String> {
+    let registry = state.0.lock().map_err(|e| e.to_string())?;
+    registry.spawn(app_handle, command, args, cwd)
+}
+
+/// Write data to a running process's stdin.
+#[tauri::command]
+pub fn proc_write_stdin(state: State<'_, ProcessState>, id: u64, data: String) -> Result<(), String> {
+    let registry = state.0.lock().map_err(|e| e.to_string())?;
+    registry.write_stdin(id, data.as_bytes())
+}
+
+/// Resize a running process's pseudo-terminal to match the frontend's terminal widget.
+#[tauri::command]
+pub fn proc_resize(state: State<'_, ProcessState>, id: u64, cols: u16, rows: u16) -> Result<(), String> {
+    let registry = state.0.lock().map_err(|e| e.to_string())?;
+    registry.resize(id, cols, rows)
+}
+
+/// Forcibly terminate a running process.
+#[tauri::command]
+pub fn proc_kill(state: State<'_, ProcessState>, id: u64) -> Result<(), String> {
+    let registry = state.0.lock().map_err(|e| e.to_string())?;
+    registry.kill(id)
+}
+
+/// Record audio (stops on silence or manual stop). `source` defaults to the microphone; pass
+/// `SystemAudio` or `Mixed` for a "transcribe what I'm hearing" meeting-notes mode.
+#[tauri::command]
+pub fn voice_record(
+    state: State<'_, VoiceState>,
+    source: Option<voice::CaptureSource>,
+) -> Result<CapturedAudio, String> {
+    let engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.record(source.unwrap_or_default())
+}
+
+/// Record audio from microphone, same stop logic as `voice_record`, but emits `voice-state` and
+/// `voice-audio-level` events as it goes so the frontend can render a live waveform/VU meter
+/// instead of only finding out once the finished WAV comes back.
 #[tauri::command]
-pub fn voice_record(state: State<'_, VoiceState>) -> Result<CapturedAudio, String> {
+pub fn voice_record_live(
+    app_handle: tauri::AppHandle,
+    state: State<'_, VoiceState>,
+) -> Result<CapturedAudio, String> {
     let engine = state.0.lock().map_err(|e| e.to_string())?;
-    engine.record()
+    engine.record_with_events(&app_handle)
 }
 
 /// Stop an ongoing recording
@@ -683,6 +1449,40 @@ pub fn voice_stop(state: State<'_, VoiceState>) -> Result<String, String> {
     Ok("Recording stopped".into())
 }
 
+/// Configure `speak`'s backend (Gateway / Local / LocalFallback) and, for local synthesis, which
+/// voice/rate/pitch to use.
+#[tauri::command]
+pub fn voice_configure_tts(
+    state: State<'_, VoiceState>,
+    backend: voice::TtsBackend,
+    tts_voice: Option<String>,
+    rate: f32,
+    pitch: f32,
+) -> Result<String, String> {
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.configure_tts(backend, tts_voice, rate, pitch);
+    Ok("TTS configured".into())
+}
+
+/// List voices the local TTS backend has installed, parallel to `list_audio_devices`.
+#[tauri::command]
+pub fn list_tts_voices() -> Vec<String> {
+    voice::list_tts_voices()
+}
+
+/// Enable/disable and tune the spectral noise gate `record` applies before WAV encoding.
+#[tauri::command]
+pub fn voice_configure_denoise(
+    state: State<'_, VoiceState>,
+    enabled: bool,
+    alpha: f32,
+    beta: f32,
+) -> Result<String, String> {
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.configure_denoise(enabled, alpha, beta);
+    Ok("Denoise configured".into())
+}
+
 /// Send text to Gateway TTS and play the response audio
 #[tauri::command]
 pub async fn voice_speak(text: String) -> Result<String, String> {
@@ -697,59 +1497,87 @@ pub async fn voice_speak(text: String) -> Result<String, String> {
     Ok("Speech played".into())
 }
 
+/// Transcribe already-captured audio entirely on-device with a bundled Whisper model — no
+/// Gateway round-trip, so it works offline and keeps audio off the network. Loads `model_path`
+/// into the engine once and keeps it resident for subsequent calls.
+#[tauri::command]
+pub fn voice_transcribe(
+    state: State<'_, VoiceState>,
+    audio: CapturedAudio,
+    model_path: String,
+) -> Result<serde_json::Value, String> {
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    if !engine.has_local_model(&model_path) {
+        engine.load_whisper_model(&model_path)?;
+    }
+    let (text, language) = engine.transcribe_local(&audio)?;
+    Ok(serde_json::json!({ "text": text, "language": language }))
+}
+
+/// Record until silence (same stop logic as `voice_record`) then transcribe locally — the
+/// offline counterpart to `chat_voice`'s record-then-upload flow, for wake-word-triggered
+/// commands that shouldn't need a network round-trip to be understood.
+#[tauri::command]
+pub fn voice_record_and_transcribe(
+    state: State<'_, VoiceState>,
+    model_path: String,
+    source: Option<voice::CaptureSource>,
+) -> Result<serde_json::Value, String> {
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    let audio = engine.record(source.unwrap_or_default())?;
+    if !engine.has_local_model(&model_path) {
+        engine.load_whisper_model(&model_path)?;
+    }
+    let (text, language) = engine.transcribe_local(&audio)?;
+    Ok(serde_json::json!({ "audio": audio, "text": text, "language": language }))
+}
+
 /// Read a screenshot and return it as a base64 data URL.
 /// Strategy: try local file first (fast), then fall back to Gateway HTTP (remote VPS).
 #[tauri::command]
 pub async fn read_screenshot(path: String, gateway_url: Option<String>) -> Result<String, String> {
-    let ext = path.rsplit('.').next().unwrap_or("png").to_lowercase();
-    let mime = match ext.as_str() {
-        "jpg" | "jpeg" => "image/jpeg",
-        "webp" => "image/webp",
-        "gif" => "image/gif",
-        _ => "image/png",
-    };
-
-    // 1) Try local file first (works when Gateway runs on same machine)
-    if let Ok(data) = tokio::fs::read(&path).await {
-        log::info!("Screenshot loaded locally: {}", path);
-        let b64 = base64::engine::general_purpose::STANDARD.encode(&data);
-        return Ok(format!("data:{};base64,{}", mime, b64));
-    }
-
-    // 2) Fallback: fetch from Gateway HTTP endpoint (works for remote VPS)
-    if let Some(gw_url) = gateway_url {
-        let normalized = path.replace("\\\\", "/").replace('\\', "/");
-        if let Some(idx) = normalized.find(".forgeai/") {
-            let rel_path = &normalized[idx + 9..]; // after ".forgeai/"
-            let url = format!("{}/api/files/{}", gw_url.trim_end_matches('/'), rel_path);
-            log::info!("Screenshot not local, fetching from Gateway: {}", url);
-
-            let client = reqwest::Client::new();
-            let mut req = client
-                .get(&url)
-                .timeout(std::time::Duration::from_secs(15));
-            // Try to add auth if credentials are available
-            if let Some(creds) = crate::connection::GatewayConnection::load_credentials() {
-                if let Some(ref token) = creds.auth_token {
-                    req = req.header("Cookie", format!("forgeai_session={}", token));
-                }
-            }
-            let resp = req
-                .send()
+    let artifact = crate::artifacts::fetch_artifact(
+        &path,
+        gateway_url.as_deref(),
+        &crate::artifacts::FetchOptions::default(),
+    )
+    .await?;
+
+    let bytes = match artifact.bytes {
+        Some(b) => b,
+        None => {
+            let path = artifact.local_path.ok_or("Artifact had neither bytes nor a local path")?;
+            tokio::fs::read(&path)
                 .await
-                .map_err(|e| format!("Gateway fetch failed: {}", e))?;
-
-            if resp.status().is_success() {
-                let bytes = resp.bytes().await.map_err(|e| format!("Read bytes failed: {}", e))?;
-                let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                return Ok(format!("data:{};base64,{}", mime, b64));
-            } else {
-                return Err(format!("Gateway returned {}: {}", resp.status(), url));
-            }
+                .map_err(|e| format!("Failed to read streamed screenshot at {}: {}", path, e))?
         }
-    }
+    };
 
-    Err(format!("Screenshot not found locally or via Gateway: {}", path))
+    let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(format!("data:{};base64,{}", artifact.mime, b64))
+}
+
+/// Fetch any Gateway-hosted or local artifact (logs, generated documents, model files, not just
+/// screenshots) — the general-purpose fetch `read_screenshot` is now a thin wrapper over. Small
+/// files come back inline as base64; anything over the streaming threshold comes back as a local
+/// temp/cache file path instead.
+#[tauri::command]
+pub async fn fetch_artifact(
+    path: String,
+    gateway_url: Option<String>,
+    force_refresh: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let opts = crate::artifacts::FetchOptions {
+        force_refresh: force_refresh.unwrap_or(false),
+    };
+    let artifact = crate::artifacts::fetch_artifact(&path, gateway_url.as_deref(), &opts).await?;
+
+    Ok(serde_json::json!({
+        "mime": artifact.mime,
+        "bytesBase64": artifact.bytes.map(|b| base64::engine::general_purpose::STANDARD.encode(&b)),
+        "localPath": artifact.local_path,
+        "fromCache": artifact.from_cache,
+    }))
 }
 
 /// List chat sessions from Gateway (companion-only)
@@ -837,6 +1665,41 @@ pub async fn delete_session(session_id: String) -> Result<serde_json::Value, Str
     resp.json().await.map_err(|e| format!("Invalid response: {}", e))
 }
 
+/// Query the local audit log of actions the Gateway has asked this Companion to run.
+/// `limit` defaults to 200 most-recent rows when omitted.
+#[tauri::command]
+pub async fn audit_query(limit: Option<u32>) -> Result<Vec<crate::audit::AuditEntry>, String> {
+    tokio::task::spawn_blocking(move || crate::audit::query_log(limit.unwrap_or(200)))
+        .await
+        .map_err(|e| format!("Audit query task failed: {}", e))?
+}
+
+/// Export the full audit log as `"json"` or `"csv"`.
+#[tauri::command]
+pub async fn audit_export(format: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || match format.as_str() {
+        "csv" => crate::audit::export_csv(),
+        "json" => crate::audit::export_json(),
+        other => Err(format!("Unknown export format '{}' — expected 'json' or 'csv'", other)),
+    })
+    .await
+    .map_err(|e| format!("Audit export task failed: {}", e))?
+}
+
+/// Enable/disable the opt-in metrics exporter and set its push endpoint/interval. Off by default —
+/// see `crate::metrics`.
+#[tauri::command]
+pub fn metrics_configure(config: crate::metrics::MetricsConfig) -> Result<String, String> {
+    crate::metrics::configure(config);
+    Ok("Metrics configured".into())
+}
+
+/// Current counters, for the frontend to show locally even when the exporter is disabled.
+#[tauri::command]
+pub fn metrics_snapshot() -> serde_json::Value {
+    crate::metrics::snapshot()
+}
+
 /// List available audio input/output devices
 #[tauri::command]
 pub fn list_audio_devices() -> Result<serde_json::Value, String> {
@@ -847,3 +1710,13 @@ pub fn list_audio_devices() -> Result<serde_json::Value, String> {
         "outputs": outputs,
     }))
 }
+
+/// Choose the microphone `voice_record`/`voice_record_and_transcribe` capture from, persisted
+/// across restarts. `name` must be one of `list_audio_devices`' `inputs`; pass `None` to reset
+/// to the host default.
+#[tauri::command]
+pub fn set_input_device(state: State<'_, VoiceState>, name: Option<String>) -> Result<String, String> {
+    let mut engine = state.0.lock().map_err(|e| e.to_string())?;
+    engine.set_input_device(name)?;
+    Ok("Input device updated".into())
+}
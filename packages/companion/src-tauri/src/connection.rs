@@ -1,15 +1,19 @@
-//! # WebSocket Connection to ForgeAI Gateway
+//! # Gateway Pairing & Credential Storage
 //!
-//! Handles secure connection, authentication (Pairing + JWT),
-//! message sending/receiving, and automatic reconnection.
+//! Value types and persistence for a paired ForgeAI Gateway: `CompanionCredentials` (stored in
+//! Windows Credential Manager, falling back to a file under the app's local data dir) and the
+//! `ConnectionState`/`ConnectionConfig` types the live connection reports through.
+//!
+//! The live WebSocket connection itself — reconnect with exponential backoff, the heartbeat
+//! watchdog, state broadcast, and auth-token refresh on rejection — lives in
+//! `commands::gateway_ws_loop`, which is the one thing that's ever actually instantiated per
+//! paired Gateway (see its module docs for the full picture). `GatewayConnection` here is just a
+//! namespace for the credential-management functions shared by every caller that needs to load,
+//! save, or delete a pairing.
 
-use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-/// Connection state
+/// Connection state, reported by `commands::gateway_ws_loop` via its per-Gateway state broadcast.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ConnectionState {
     Disconnected,
@@ -20,25 +24,24 @@ pub enum ConnectionState {
     Error(String),
 }
 
-/// Message from Gateway
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct GatewayMessage {
-    #[serde(rename = "type")]
-    pub msg_type: String,
-    pub content: Option<String>,
-    pub session_id: Option<String>,
-    pub tool_call: Option<serde_json::Value>,
-    pub done: Option<bool>,
+/// Heartbeat tuning for the live WebSocket connection. Defaults match the Gateway's own advised
+/// cadence. `commands::gateway_ws_loop` uses `ConnectionConfig::default()` for every connection;
+/// an interval advertised by the Gateway is not read.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    /// How often to send a `health.ping` keepalive while connected. It is a JSON text frame, not a
+    /// WebSocket-level `Message::Ping`.
+    pub heartbeat_interval_secs: u64,
+    /// If no pong or other inbound traffic arrives within this many seconds, the connection is
+    /// considered dead and torn down for the reconnect supervisor to pick up.
+    pub heartbeat_timeout_secs: u64,
 }
 
-/// Message to send to Gateway
-#[derive(Debug, Clone, Serialize)]
-pub struct OutgoingMessage {
-    #[serde(rename = "type")]
-    pub msg_type: String,
-    pub content: String,
-    pub session_id: Option<String>,
-    pub channel: String,
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        // Timeout = 2 missed heartbeat intervals, per the module's dead-connection detection.
+        Self { heartbeat_interval_secs: 15, heartbeat_timeout_secs: 30 }
+    }
 }
 
 /// Credentials stored in Windows Credential Manager
@@ -49,39 +52,39 @@ pub struct CompanionCredentials {
     pub role: String,
     #[serde(default)]
     pub auth_token: Option<String>,
+    /// SHA-256 fingerprint (hex) of a certificate the user has pinned this Gateway to. When set,
+    /// connections require an exact match instead of ordinary chain-of-trust validation — see
+    /// `crate::tls`.
+    #[serde(default)]
+    pub pinned_cert_sha256: Option<String>,
 }
 
-/// ForgeAI Gateway connection manager
-pub struct GatewayConnection {
-    state: Arc<Mutex<ConnectionState>>,
-    credentials: Arc<Mutex<Option<CompanionCredentials>>>,
-    outgoing_tx: Option<mpsc::UnboundedSender<String>>,
-    incoming_tx: mpsc::UnboundedSender<GatewayMessage>,
-}
+/// Namespace for loading/saving/deleting paired-Gateway credentials. Holds no state of its own —
+/// the live connection that actually uses these credentials lives in `commands::gateway_ws_loop`.
+pub struct GatewayConnection;
 
 impl GatewayConnection {
-    pub fn new(incoming_tx: mpsc::UnboundedSender<GatewayMessage>) -> Self {
-        Self {
-            state: Arc::new(Mutex::new(ConnectionState::Disconnected)),
-            credentials: Arc::new(Mutex::new(None)),
-            outgoing_tx: None,
-            incoming_tx,
-        }
-    }
-
-    /// Get current connection state
-    pub async fn get_state(&self) -> ConnectionState {
-        self.state.lock().await.clone()
-    }
-
     /// Get the file path for credential storage fallback
     fn creds_file_path() -> Option<std::path::PathBuf> {
         dirs::data_local_dir().map(|d| d.join("forgeai-companion").join("credentials.json"))
     }
 
-    /// Save credentials to Windows Credential Manager + file fallback
+    /// Save credentials to Windows Credential Manager + file fallback. Upserts by
+    /// `companion_id` into the stored list rather than overwriting it, so pairing with a second
+    /// Gateway doesn't drop the first — a companion can be paired with more than one Gateway
+    /// (e.g. a personal and a work instance) at once.
     pub fn save_credentials(creds: &CompanionCredentials) -> Result<(), String> {
-        let json = serde_json::to_string(creds).map_err(|e| format!("Serialize error: {}", e))?;
+        let mut all = Self::load_all_credentials();
+        match all.iter_mut().find(|c| c.companion_id == creds.companion_id) {
+            Some(existing) => *existing = creds.clone(),
+            None => all.push(creds.clone()),
+        }
+        Self::save_all_credentials(&all)
+    }
+
+    /// Persist the full list of paired Gateways' credentials.
+    fn save_all_credentials(all: &[CompanionCredentials]) -> Result<(), String> {
+        let json = serde_json::to_string(all).map_err(|e| format!("Serialize error: {}", e))?;
 
         // Try keyring first
         if let Ok(entry) = keyring::Entry::new("forgeai-companion", "credentials") {
@@ -95,38 +98,59 @@ impl GatewayConnection {
             }
             std::fs::write(&path, &json)
                 .map_err(|e| format!("File save error: {}", e))?;
-            log::info!("Credentials saved to {}", path.display());
+            log::info!("Credentials saved to {} ({} Gateway(s))", path.display(), all.len());
         }
 
         Ok(())
     }
 
-    /// Load credentials from Windows Credential Manager, fallback to file
-    pub fn load_credentials() -> Option<CompanionCredentials> {
-        // Try keyring first
+    /// Load every paired Gateway's credentials from Windows Credential Manager, falling back to
+    /// file. Tolerates the pre-registry single-object format so existing pairings still load.
+    pub fn load_all_credentials() -> Vec<CompanionCredentials> {
+        let parse = |json: &str| -> Option<Vec<CompanionCredentials>> {
+            if let Ok(list) = serde_json::from_str::<Vec<CompanionCredentials>>(json) {
+                return Some(list);
+            }
+            serde_json::from_str::<CompanionCredentials>(json)
+                .ok()
+                .map(|c| vec![c])
+        };
+
         if let Ok(entry) = keyring::Entry::new("forgeai-companion", "credentials") {
             if let Ok(json) = entry.get_password() {
-                if let Ok(creds) = serde_json::from_str::<CompanionCredentials>(&json) {
-                    return Some(creds);
+                if let Some(list) = parse(&json) {
+                    return list;
                 }
             }
         }
 
-        // Fallback to file
         if let Some(path) = Self::creds_file_path() {
             if let Ok(json) = std::fs::read_to_string(&path) {
-                if let Ok(creds) = serde_json::from_str::<CompanionCredentials>(&json) {
-                    log::info!("Credentials loaded from file fallback");
-                    return Some(creds);
+                if let Some(list) = parse(&json) {
+                    log::info!("Credentials loaded from file fallback ({} Gateway(s))", list.len());
+                    return list;
                 }
             }
         }
 
         log::warn!("No credentials found in keyring or file");
-        None
+        Vec::new()
+    }
+
+    /// Load the first paired Gateway's credentials — kept for callers (chat, voice, the file
+    /// proxy) that only ever talk to one Gateway at a time.
+    pub fn load_credentials() -> Option<CompanionCredentials> {
+        Self::load_all_credentials().into_iter().next()
+    }
+
+    /// Load one specific Gateway's credentials by `companion_id`.
+    pub fn load_credentials_for(companion_id: &str) -> Option<CompanionCredentials> {
+        Self::load_all_credentials()
+            .into_iter()
+            .find(|c| c.companion_id == companion_id)
     }
 
-    /// Delete stored credentials from both keyring and file
+    /// Delete every paired Gateway's credentials from both keyring and file.
     pub fn delete_credentials() -> Result<(), String> {
         // Try keyring
         if let Ok(entry) = keyring::Entry::new("forgeai-companion", "credentials") {
@@ -141,203 +165,60 @@ impl GatewayConnection {
         Ok(())
     }
 
-    /// Pair with Gateway using a pairing code from the Dashboard
-    pub async fn pair(
-        &mut self,
-        gateway_url: &str,
-        pairing_code: &str,
-    ) -> Result<(), String> {
-        *self.state.lock().await = ConnectionState::Connecting;
-
-        let base_url = gateway_url.trim_end_matches('/');
-        let url = format!("{}/api/pairing/claim", base_url);
-
-        let client = reqwest::Client::new();
-        let resp = client
-            .post(&url)
-            .json(&serde_json::json!({
-                "code": pairing_code,
-                "deviceName": "ForgeAI Companion (Windows)",
-                "deviceType": "desktop"
-            }))
-            .timeout(std::time::Duration::from_secs(10))
-            .send()
-            .await
-            .map_err(|e| format!("Connection failed: {}", e))?;
-
-        if !resp.status().is_success() {
-            let text = resp.text().await.unwrap_or_default();
-            *self.state.lock().await = ConnectionState::Error(text.clone());
-            return Err(format!("Pairing failed: {}", text));
-        }
-
-        let data: serde_json::Value = resp
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))?;
-
-        let creds = CompanionCredentials {
-            gateway_url: base_url.to_string(),
-            companion_id: data["companionId"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            role: data["role"]
-                .as_str()
-                .unwrap_or("user")
-                .to_string(),
-            auth_token: data["authToken"]
-                .as_str()
-                .map(|s| s.to_string()),
-        };
-
-        Self::save_credentials(&creds)?;
-        *self.credentials.lock().await = Some(creds);
-        *self.state.lock().await = ConnectionState::Authenticated;
-
-        log::info!("Paired with Gateway at {}", base_url);
-        Ok(())
+    /// Remove one paired Gateway's credentials, leaving the rest untouched.
+    pub fn delete_credentials_for(companion_id: &str) -> Result<(), String> {
+        let remaining: Vec<CompanionCredentials> = Self::load_all_credentials()
+            .into_iter()
+            .filter(|c| c.companion_id != companion_id)
+            .collect();
+        Self::save_all_credentials(&remaining)
     }
+}
 
-    /// Connect to Gateway WebSocket
-    pub async fn connect(&mut self) -> Result<(), String> {
-        let creds = {
-            let lock = self.credentials.lock().await;
-            lock.clone()
-                .or_else(|| Self::load_credentials())
-                .ok_or("No credentials — please pair first")?
-        };
-
-        *self.state.lock().await = ConnectionState::Connecting;
-
-        // Build WebSocket URL
-        let ws_url = creds
-            .gateway_url
-            .replace("https://", "wss://")
-            .replace("http://", "ws://");
-        let ws_url = format!("{}/ws?companionId={}", ws_url, creds.companion_id);
-
-        let (ws_stream, _) = connect_async(&ws_url)
-            .await
-            .map_err(|e| format!("WebSocket connection failed: {}", e))?;
-
-        let (mut write, mut read) = ws_stream.split();
-
-        // Outgoing channel
-        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
-        let action_tx = tx.clone(); // Clone before moving tx into self
-        self.outgoing_tx = Some(tx);
-
-        // Send task — forwards outgoing messages to WebSocket
-        tokio::spawn(async move {
-            while let Some(msg) = rx.recv().await {
-                if write.send(Message::Text(msg.into())).await.is_err() {
-                    break;
-                }
-            }
-        });
-
-        // Receive task — forwards incoming messages to app + handles action requests
-        let incoming_tx = self.incoming_tx.clone();
-        let state = self.state.clone();
-
-        tokio::spawn(async move {
-            while let Some(Ok(msg)) = read.next().await {
-                match msg {
-                    Message::Text(text) => {
-                        // Try to parse as a raw JSON value first to check type
-                        if let Ok(raw) = serde_json::from_str::<serde_json::Value>(&text) {
-                            if raw.get("type").and_then(|t| t.as_str()) == Some("action_request") {
-                                // Handle action request from Gateway agent
-                                let request_id = raw.get("requestId").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                let action = raw.get("action").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                let params = raw.get("params").cloned().unwrap_or(serde_json::json!({}));
-
-                                log::info!("Action request from Gateway: {} ({})", action, request_id);
-
-                                // Build ActionRequest from the params
-                                let action_req = crate::local_actions::ActionRequest {
-                                    action: action.clone(),
-                                    path: params.get("path").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    command: params.get("command").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    content: params.get("content").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    process_name: params.get("process_name").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    app_name: params.get("app_name").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    cwd: params.get("cwd").and_then(|v| v.as_str()).map(|s| s.to_string()),
-                                    confirmed: true, // Agent-initiated actions are pre-confirmed
-                                };
-
-                                // Execute locally on Windows
-                                let result = crate::local_actions::execute(&action_req);
-                                log::info!("Action result: {} success={}", action, result.success);
-
-                                // Send result back via WebSocket
-                                let response = serde_json::json!({
-                                    "type": "action_result",
-                                    "requestId": request_id,
-                                    "success": result.success,
-                                    "output": result.output,
-                                });
-                                if let Ok(json) = serde_json::to_string(&response) {
-                                    let _ = action_tx.send(json);
-                                }
-                                continue;
-                            }
-                        }
-
-                        // Normal message — forward to app
-                        if let Ok(gateway_msg) =
-                            serde_json::from_str::<GatewayMessage>(&text)
-                        {
-                            let _ = incoming_tx.send(gateway_msg);
-                        }
-                    }
-                    Message::Close(_) => {
-                        *state.lock().await = ConnectionState::Reconnecting;
-                        break;
-                    }
-                    _ => {}
-                }
-            }
-        });
-
-        *self.state.lock().await = ConnectionState::Connected;
-        *self.credentials.lock().await = Some(creds);
+/// Whether a WebSocket handshake error is an HTTP 401 — i.e. the Gateway rejected `auth_token`
+/// rather than the connection simply failing to establish. Used by `commands::gateway_ws_loop` to
+/// tell an auth rejection apart from an ordinary connect failure.
+pub fn is_auth_rejection(err: &tokio_tungstenite::tungstenite::Error) -> bool {
+    matches!(
+        err,
+        tokio_tungstenite::tungstenite::Error::Http(resp) if resp.status().as_u16() == 401
+    )
+}
 
-        log::info!("Connected to Gateway WebSocket");
-        Ok(())
+/// Ask the Gateway for a fresh `auth_token` in exchange for the current one and persist it, so routine token rotation
+/// doesn't force the user to re-pair. Called by `commands::gateway_ws_loop` on a 401 at connect
+/// time or an `auth_error` frame mid-connection — both cases `is_auth_rejection`/the Gateway tell
+/// us the *old* token, not the pairing itself, is the problem.
+pub async fn refresh_auth_token(creds: &CompanionCredentials) -> Result<CompanionCredentials, String> {
+    let url = format!("{}/api/companion/refresh", creds.gateway_url.trim_end_matches('/'));
+
+    let client = crate::tls::http_client(Some(creds))?;
+    let resp = client
+        .post(&url)
+        .json(&serde_json::json!({
+            "companionId": creds.companion_id,
+            "authToken": creds.auth_token,
+        }))
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("Refresh request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Gateway returned HTTP {} for token refresh", resp.status()));
     }
 
-    /// Send a chat message to the Gateway
-    pub fn send_message(&self, content: &str, session_id: Option<&str>) -> Result<(), String> {
-        let tx = self
-            .outgoing_tx
-            .as_ref()
-            .ok_or("Not connected")?;
-
-        let msg = OutgoingMessage {
-            msg_type: "chat".to_string(),
-            content: content.to_string(),
-            session_id: session_id.map(|s| s.to_string()),
-            channel: "companion".to_string(),
-        };
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("Invalid refresh response: {}", e))?;
 
-        let json = serde_json::to_string(&msg).map_err(|e| format!("Serialize error: {}", e))?;
-        tx.send(json).map_err(|e| format!("Send error: {}", e))
-    }
+    let auth_token = body["authToken"]
+        .as_str()
+        .ok_or("Refresh response missing authToken")?
+        .to_string();
 
-    /// Check if connected
-    pub async fn is_connected(&self) -> bool {
-        matches!(
-            *self.state.lock().await,
-            ConnectionState::Connected | ConnectionState::Authenticated
-        )
-    }
-
-    /// Disconnect
-    pub async fn disconnect(&mut self) {
-        self.outgoing_tx = None;
-        *self.state.lock().await = ConnectionState::Disconnected;
-        log::info!("Disconnected from Gateway");
-    }
+    let refreshed = CompanionCredentials { auth_token: Some(auth_token), ..creds.clone() };
+    GatewayConnection::save_credentials(&refreshed)?;
+    Ok(refreshed)
 }
@@ -27,6 +27,22 @@ pub struct ActionRequest {
     pub app_name: Option<String>,
     pub cwd: Option<String>,
     pub confirmed: bool,
+    /// Write via temp-file-then-swap instead of truncating in place (default true)
+    #[serde(default = "default_atomic")]
+    pub atomic: bool,
+    /// Keep the replaced original as `<name>.bak` when overwriting an existing file
+    #[serde(default)]
+    pub backup: bool,
+    /// Hash algorithm for `hash_file` and verified reads/writes: "sha256" (default), "sha1", or "md5"
+    pub algo: Option<String>,
+    /// For `read_file`: reject the read if the file's digest doesn't match this hex string
+    pub expected_hash: Option<String>,
+    /// For `process_info`: target by PID instead of `process_name`
+    pub pid: Option<u32>,
+}
+
+fn default_atomic() -> bool {
+    true
 }
 
 impl ActionResult {
@@ -72,6 +88,7 @@ pub fn execute(request: &ActionRequest) -> ActionResult {
         // ─── File Operations ───
         "read_file" => read_file(request),
         "write_file" => write_file(request),
+        "hash_file" => hash_file(request),
         "delete_file" => delete_file(request),
         "list_dir" => list_dir(request),
         "create_dir" => create_dir(request),
@@ -88,6 +105,7 @@ pub fn execute(request: &ActionRequest) -> ActionResult {
         "open_url" => open_url(request),
         "list_processes" => list_processes(),
         "kill_process" => kill_process(request),
+        "process_info" => process_info(request),
 
         // ─── System Info ───
         "system_info" => system_info(),
@@ -101,11 +119,89 @@ pub fn execute(request: &ActionRequest) -> ActionResult {
                 risk: RiskLevel::Blocked,
                 reason: "Unknown action".into(),
                 requires_confirmation: false,
+                requires_elevation: false,
             },
         },
     }
 }
 
+// ─── Pipelines ────────────────────────────────────────
+
+/// An ordered multi-step plan submitted by the LLM for single-shot review — e.g.
+/// create dir → write file → run shell → move result — instead of one round-trip per step.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pipeline {
+    pub name: Option<String>,
+    pub steps: Vec<ActionRequest>,
+    /// Pre-authorize every medium-risk step so the user approves the whole plan once
+    #[serde(default)]
+    pub confirmed: bool,
+}
+
+/// Result of running a `Pipeline` — one `ActionResult` per step actually executed.
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineResult {
+    pub success: bool,
+    pub steps: Vec<ActionResult>,
+    /// Index of the step that halted the pipeline (failure or pending confirmation), if any
+    pub stopped_at: Option<usize>,
+    /// True if the pipeline stopped because a step needs user confirmation
+    pub needs_confirmation: bool,
+    /// Steps that were never reached
+    pub remaining: usize,
+}
+
+/// Run every step of a `Pipeline` in order, threading a shared working directory between
+/// steps and halting at the first step whose `ActionResult.success` is false — including a
+/// step that only needs confirmation, unless `Pipeline::confirmed` pre-authorized it.
+pub fn execute_pipeline(pipeline: &Pipeline) -> PipelineResult {
+    let mut steps = Vec::with_capacity(pipeline.steps.len());
+    let mut shared_cwd: Option<String> = None;
+
+    for (idx, step) in pipeline.steps.iter().enumerate() {
+        let mut req = step.clone();
+        if req.cwd.is_none() {
+            req.cwd = shared_cwd.clone();
+        }
+        let mut result = execute(&req);
+        // A step held for confirmation reports its verdict before doing anything, so a
+        // medium-risk step can be re-run as pre-authorized.
+        if pipeline.confirmed && !req.confirmed && result.safety.requires_confirmation
+            && result.safety.risk == safety::RiskLevel::Medium
+        {
+            req.confirmed = true;
+            result = execute(&req);
+        }
+        let halted = !result.success;
+        let needs_confirmation = halted && result.safety.requires_confirmation;
+
+        // A successful create_dir becomes the shared cwd for subsequent steps
+        if req.action == "create_dir" && result.success {
+            shared_cwd = req.path.clone();
+        }
+
+        steps.push(result);
+
+        if halted {
+            return PipelineResult {
+                success: false,
+                steps,
+                stopped_at: Some(idx),
+                needs_confirmation,
+                remaining: pipeline.steps.len() - idx - 1,
+            };
+        }
+    }
+
+    PipelineResult {
+        success: true,
+        steps,
+        stopped_at: None,
+        needs_confirmation: false,
+        remaining: 0,
+    }
+}
+
 // ─── File Operations ─────────────────────────────────
 
 fn read_file(req: &ActionRequest) -> ActionResult {
@@ -113,14 +209,29 @@ fn read_file(req: &ActionRequest) -> ActionResult {
         Some(p) => p,
         None => return ActionResult::err("path is required".into(), safe_verdict()),
     };
-    let verdict = safety::check_file_operation("read", path);
+    let verdict = safety::check_file_operation("read", path, None);
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
 
-    match std::fs::read_to_string(path) {
-        Ok(content) => {
-            // Limit output to 50KB to avoid overwhelming the LLM
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            if let Some(expected) = &req.expected_hash {
+                let algo = req.algo.as_deref().unwrap_or("sha256");
+                match hash_bytes(algo, &bytes) {
+                    Ok(actual) if actual.eq_ignore_ascii_case(expected) => {}
+                    Ok(actual) => {
+                        return ActionResult::err(
+                            format!("Hash mismatch for {}: expected {}, got {} ({})", path, expected, actual, algo),
+                            verdict,
+                        );
+                    }
+                    Err(e) => return ActionResult::err(e, verdict),
+                }
+            }
+
+            // Limit output to 50KB to avoid overwhelming the LLM (hashing above runs on the full bytes)
+            let content = String::from_utf8_lossy(&bytes).into_owned();
             let truncated = if content.len() > 50_000 {
                 format!("{}...\n\n[Truncated: {} bytes total]", &content[..50_000], content.len())
             } else {
@@ -141,7 +252,7 @@ fn write_file(req: &ActionRequest) -> ActionResult {
         Some(c) => c,
         None => return ActionResult::err("content is required".into(), safe_verdict()),
     };
-    let verdict = safety::check_file_operation("write", path);
+    let verdict = safety::check_file_operation("write", path, Some(content.as_bytes()));
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
@@ -151,18 +262,156 @@ fn write_file(req: &ActionRequest) -> ActionResult {
         let _ = std::fs::create_dir_all(parent);
     }
 
-    match std::fs::write(path, content) {
-        Ok(()) => ActionResult::ok(format!("Written {} bytes to {}", content.len(), path), verdict),
+    let algo = req.algo.as_deref().unwrap_or("sha256");
+    let digest = hash_bytes(algo, content.as_bytes());
+
+    if !req.atomic {
+        if req.backup && Path::new(path).exists() {
+            if let Err(e) = std::fs::copy(path, append_suffix(Path::new(path), ".bak")) {
+                return ActionResult::err(format!("Failed to create backup: {}", e), verdict);
+            }
+        }
+        return match std::fs::write(path, content) {
+            Ok(()) => ActionResult::ok(write_summary(path, content.len(), false, algo, &digest), verdict),
+            Err(e) => ActionResult::err(format!("Failed to write: {}", e), verdict),
+        };
+    }
+
+    match atomic_write(Path::new(path), content.as_bytes(), req.backup) {
+        Ok(()) => ActionResult::ok(write_summary(path, content.len(), true, algo, &digest), verdict),
         Err(e) => ActionResult::err(format!("Failed to write: {}", e), verdict),
     }
 }
 
+fn write_summary(path: &str, bytes: usize, atomic: bool, algo: &str, digest: &Result<String, String>) -> String {
+    let mode = if atomic { " (atomic)" } else { "" };
+    match digest {
+        Ok(hash) => format!("Written {} bytes to {}{} — {}={}", bytes, path, mode, algo, hash),
+        Err(_) => format!("Written {} bytes to {}{}", bytes, path, mode),
+    }
+}
+
+/// Write `content` to `path` without ever leaving it half-written: the new bytes land in a
+/// sibling temp file first, which is flushed and fsync'd, then atomically swapped into place.
+/// A failed swap leaves the original file exactly as it was and bubbles up the error.
+fn atomic_write(path: &Path, content: &[u8], backup: bool) -> Result<(), String> {
+    use std::io::Write;
+
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let nance = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+        ^ (std::process::id() as u64);
+    let tmp_path = dir.join(format!("{}.forge-tmp-{:x}", file_name, nance));
+
+    let write_result = (|| -> std::io::Result<()> {
+        let mut tmp = std::fs::File::create(&tmp_path)?;
+        tmp.write_all(content)?;
+        tmp.sync_all()
+    })();
+
+    if let Err(e) = write_result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(format!("Failed to stage temp file: {}", e));
+    }
+
+    let existed = path.exists();
+
+    if backup && existed {
+        let bak_path = append_suffix(path, ".bak");
+        if let Err(e) = std::fs::copy(path, &bak_path) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(format!("Failed to create backup: {}", e));
+        }
+    }
+
+    let swap_result = if existed {
+        replace_existing(&tmp_path, path)
+    } else {
+        std::fs::rename(&tmp_path, path).map_err(|e| format!("Rename failed: {}", e))
+    };
+
+    if swap_result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    swap_result
+}
+
+/// Append a literal suffix (e.g. ".bak") to a path's file name, keeping the existing extension.
+fn append_suffix(path: &Path, suffix: &str) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    std::path::PathBuf::from(name)
+}
+
+/// Atomically swap `tmp` into `dest`, which already exists.
+/// Windows: use ReplaceFileW so ACLs/attributes on `dest` are preserved.
+/// Elsewhere: `rename` is atomic within a filesystem.
+#[cfg(windows)]
+fn replace_existing(tmp: &Path, dest: &Path) -> Result<(), String> {
+    win_replace::replace_file(dest, tmp)
+}
+
+#[cfg(not(windows))]
+fn replace_existing(tmp: &Path, dest: &Path) -> Result<(), String> {
+    std::fs::rename(tmp, dest).map_err(|e| format!("Rename failed: {}", e))
+}
+
+#[cfg(windows)]
+mod win_replace {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    extern "system" {
+        fn ReplaceFileW(
+            lp_replaced_file_name: *const u16,
+            lp_replacement_file_name: *const u16,
+            lp_backup_file_name: *const u16,
+            dw_replace_flags: u32,
+            lp_exclude: *mut std::ffi::c_void,
+            lp_reserved: *mut std::ffi::c_void,
+        ) -> i32;
+    }
+
+    fn to_wide(path: &Path) -> Vec<u16> {
+        OsStr::new(path).encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    /// Replace `original` with `replacement` in a single atomic step (no backup kept by Windows —
+    /// our own `.bak` handling above already covers that).
+    pub fn replace_file(original: &Path, replacement: &Path) -> Result<(), String> {
+        let original_w = to_wide(original);
+        let replacement_w = to_wide(replacement);
+        let ok = unsafe {
+            ReplaceFileW(
+                original_w.as_ptr(),
+                replacement_w.as_ptr(),
+                std::ptr::null(),
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            Err(format!(
+                "ReplaceFileW failed: {}",
+                std::io::Error::last_os_error()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 fn delete_file(req: &ActionRequest) -> ActionResult {
     let path = match &req.path {
         Some(p) => p,
         None => return ActionResult::err("path is required".into(), safe_verdict()),
     };
-    let verdict = safety::check_file_operation("delete", path);
+    let verdict = safety::check_file_operation("delete", path, None);
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
@@ -185,7 +434,7 @@ fn delete_file(req: &ActionRequest) -> ActionResult {
 
 fn list_dir(req: &ActionRequest) -> ActionResult {
     let path = req.path.as_deref().unwrap_or(".");
-    let verdict = safety::check_file_operation("list", path);
+    let verdict = safety::check_file_operation("list", path, None);
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
@@ -220,7 +469,7 @@ fn create_dir(req: &ActionRequest) -> ActionResult {
         Some(p) => p,
         None => return ActionResult::err("path is required".into(), safe_verdict()),
     };
-    let verdict = safety::check_file_operation("create", path);
+    let verdict = safety::check_file_operation("create", path, None);
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
@@ -273,8 +522,8 @@ fn move_file(req: &ActionRequest) -> ActionResult {
         Some(c) => c.clone(),
         None => return ActionResult::err("content (destination) is required".into(), safe_verdict()),
     };
-    let verdict_from = safety::check_file_operation("move", &from);
-    let verdict_to = safety::check_file_operation("write", &to);
+    let verdict_from = safety::check_file_operation("move", &from, None);
+    let verdict_to = safety::check_file_operation("write", &to, None);
     if !verdict_from.allowed {
         return ActionResult::blocked(verdict_from);
     }
@@ -297,7 +546,7 @@ fn copy_file(req: &ActionRequest) -> ActionResult {
         Some(c) => c.clone(),
         None => return ActionResult::err("content (destination) is required".into(), safe_verdict()),
     };
-    let verdict = safety::check_file_operation("copy", &to);
+    let verdict = safety::check_file_operation("copy", &to, None);
     if !verdict.allowed {
         return ActionResult::blocked(verdict);
     }
@@ -311,6 +560,95 @@ fn copy_file(req: &ActionRequest) -> ActionResult {
     }
 }
 
+fn hash_file(req: &ActionRequest) -> ActionResult {
+    let path = match &req.path {
+        Some(p) => p,
+        None => return ActionResult::err("path is required".into(), safe_verdict()),
+    };
+    let verdict = safety::check_file_operation("read", path, None);
+    if !verdict.allowed {
+        return ActionResult::blocked(verdict);
+    }
+    let algo = req.algo.as_deref().unwrap_or("sha256");
+
+    match hash_file_streamed(Path::new(path), algo) {
+        Ok((digest, bytes)) => ActionResult::ok(format!("{}={} ({} bytes)", algo, digest, bytes), verdict),
+        Err(e) => ActionResult::err(e, verdict),
+    }
+}
+
+// ─── Hashing ──────────────────────────────────────────
+
+/// Digest a file without buffering it fully in memory — read in fixed-size chunks instead.
+fn hash_file_streamed(path: &Path, algo: &str) -> Result<(String, u64), String> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open: {}", e))?;
+    let mut hasher = AnyHasher::new(algo)?;
+    let mut buf = [0u8; 64 * 1024];
+    let mut total: u64 = 0;
+
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("Read error: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+    }
+
+    Ok((hasher.finalize_hex(), total))
+}
+
+fn hash_bytes(algo: &str, data: &[u8]) -> Result<String, String> {
+    let mut hasher = AnyHasher::new(algo)?;
+    hasher.update(data);
+    Ok(hasher.finalize_hex())
+}
+
+/// Small dispatcher over the supported digest algorithms so callers don't care which one is active.
+enum AnyHasher {
+    Sha256(sha2::Sha256),
+    Sha1(sha1::Sha1),
+    Md5(md5::Context),
+}
+
+impl AnyHasher {
+    fn new(algo: &str) -> Result<Self, String> {
+        use sha2::Digest as _;
+        match algo.to_lowercase().as_str() {
+            "sha256" => Ok(Self::Sha256(sha2::Sha256::new())),
+            "sha1" => Ok(Self::Sha1(sha1::Sha1::new())),
+            "md5" => Ok(Self::Md5(md5::Context::new())),
+            other => Err(format!("Unsupported hash algorithm: {}", other)),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        use sha1::Digest as _;
+        use sha2::Digest as _;
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Md5(h) => h.consume(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        use sha1::Digest as _;
+        use sha2::Digest as _;
+        match self {
+            Self::Sha256(h) => to_hex(&h.finalize()),
+            Self::Sha1(h) => to_hex(&h.finalize()),
+            Self::Md5(h) => format!("{:x}", h.compute()),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 // ─── Shell Commands ──────────────────────────────────
 
 fn run_shell(req: &ActionRequest) -> ActionResult {
@@ -326,20 +664,9 @@ fn run_shell(req: &ActionRequest) -> ActionResult {
         return ActionResult::needs_confirm(verdict);
     }
 
-    let mut cmd = Command::new("powershell.exe");
-    cmd.args(["-NoProfile", "-NonInteractive", "-Command", command]);
-    if let Some(cwd) = &req.cwd {
-        let cwd_path = std::path::Path::new(cwd);
-        if cwd_path.exists() {
-            cmd.current_dir(cwd_path);
-        }
-    }
-    let output = cmd.output();
-
-    match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+    let cwd = req.cwd.as_deref().map(Path::new);
+    match crate::platform::current().run_shell(command, cwd) {
+        Ok((stdout, stderr)) => {
             let combined = if stderr.is_empty() {
                 stdout
             } else {
@@ -357,6 +684,57 @@ fn run_shell(req: &ActionRequest) -> ActionResult {
     }
 }
 
+/// Largest byte offset `<= idx` that lands on a UTF-8 char boundary in `s` — lets us truncate
+/// `from_utf8_lossy` output at a fixed byte budget without risking a mid-character panic.
+fn floor_char_boundary(s: &str, mut idx: usize) -> usize {
+    if idx >= s.len() {
+        return s.len();
+    }
+    while idx > 0 && !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// Like `run_shell`, but invokes `on_line("stdout" | "stderr", line)` as output arrives instead
+/// of buffering the whole command until it exits, and reports the process exit code alongside
+/// the result — for long-running commands where a caller wants live output.
+pub fn run_shell_streaming(
+    req: &ActionRequest,
+    on_line: &mut dyn FnMut(&str, &str),
+) -> (ActionResult, Option<i32>) {
+    let command = match &req.command {
+        Some(c) => c,
+        None => return (ActionResult::err("command is required".into(), safe_verdict()), None),
+    };
+    let verdict = safety::check_shell_command(command);
+    if !verdict.allowed {
+        return (ActionResult::blocked(verdict), None);
+    }
+    if verdict.requires_confirmation && !req.confirmed {
+        return (ActionResult::needs_confirm(verdict), None);
+    }
+
+    let cwd = req.cwd.as_deref().map(Path::new);
+    match crate::platform::current().run_shell_streaming(command, cwd, on_line) {
+        Ok((stdout, stderr, exit_code)) => {
+            let combined = if stderr.is_empty() {
+                stdout
+            } else {
+                format!("{}\n[STDERR]\n{}", stdout, stderr)
+            };
+            let truncated = if combined.len() > 30_000 {
+                let cut = floor_char_boundary(&combined, 30_000);
+                format!("{}...\n[Truncated: {} chars]", &combined[..cut], combined.len())
+            } else {
+                combined
+            };
+            (ActionResult::ok(truncated, verdict), exit_code)
+        }
+        Err(e) => (ActionResult::err(format!("Failed to execute: {}", e), verdict), None),
+    }
+}
+
 // ─── Application Control ─────────────────────────────
 
 fn open_app(req: &ActionRequest) -> ActionResult {
@@ -370,15 +748,12 @@ fn open_app(req: &ActionRequest) -> ActionResult {
         risk: RiskLevel::Medium,
         reason: format!("Opening application: {}", app),
         requires_confirmation: false,
+        requires_elevation: false,
     };
 
-    let result = Command::new("cmd")
-        .args(["/C", "start", "", app])
-        .spawn();
-
-    match result {
-        Ok(_) => ActionResult::ok(format!("Launched: {}", app), verdict),
-        Err(e) => ActionResult::err(format!("Failed to open {}: {}", app, e), verdict),
+    match crate::platform::current().open_app(app) {
+        Ok(()) => ActionResult::ok(format!("Launched: {}", app), verdict),
+        Err(e) => ActionResult::err(e, verdict),
     }
 }
 
@@ -388,39 +763,25 @@ fn open_url(req: &ActionRequest) -> ActionResult {
         None => return ActionResult::err("path (URL) is required".into(), safe_verdict()),
     };
 
-    let result = Command::new("cmd")
-        .args(["/C", "start", "", url])
-        .spawn();
-
-    match result {
-        Ok(_) => ActionResult::ok(
+    match crate::platform::current().open_url(url) {
+        Ok(()) => ActionResult::ok(
             format!("Opened URL: {}", url),
             SafetyVerdict {
                 allowed: true,
                 risk: RiskLevel::Low,
                 reason: "Opening URL in default browser".into(),
                 requires_confirmation: false,
+                requires_elevation: false,
             },
         ),
-        Err(e) => ActionResult::err(format!("Failed: {}", e), safe_verdict()),
+        Err(e) => ActionResult::err(e, safe_verdict()),
     }
 }
 
 fn list_processes() -> ActionResult {
-    let output = Command::new("tasklist")
-        .args(["/FO", "CSV", "/NH"])
-        .output();
-
-    match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            let lines: Vec<&str> = stdout.lines().take(50).collect();
-            ActionResult::ok(
-                format!("Top 50 processes:\n{}", lines.join("\n")),
-                safe_verdict(),
-            )
-        }
-        Err(e) => ActionResult::err(format!("Failed: {}", e), safe_verdict()),
+    match crate::platform::current().list_processes() {
+        Ok(listing) => ActionResult::ok(listing, safe_verdict()),
+        Err(e) => ActionResult::err(e, safe_verdict()),
     }
 }
 
@@ -437,46 +798,57 @@ fn kill_process(req: &ActionRequest) -> ActionResult {
         return ActionResult::needs_confirm(verdict);
     }
 
-    let result = Command::new("taskkill")
-        .args(["/IM", name, "/F"])
-        .output();
+    match crate::platform::current().kill_process(name) {
+        Ok(msg) => ActionResult::ok(msg, verdict),
+        Err(e) => ActionResult::err(e, verdict),
+    }
+}
 
-    match result {
-        Ok(out) => {
-            let msg = String::from_utf8_lossy(&out.stdout).to_string();
-            ActionResult::ok(msg, verdict)
-        }
-        Err(e) => ActionResult::err(format!("Failed: {}", e), verdict),
+/// Structured diagnostics (memory, threads, handles, parent pid, runtime) for one process —
+/// enough for the LLM to judge whether it's hung or leaking before reaching for `kill_process`.
+fn process_info(req: &ActionRequest) -> ActionResult {
+    let target = if let Some(pid) = req.pid {
+        pid.to_string()
+    } else if let Some(name) = &req.process_name {
+        name.clone()
+    } else {
+        return ActionResult::err("process_name or pid is required".into(), safe_verdict());
+    };
+
+    let verdict = safety::check_process_access(&target, "query_information");
+    if !verdict.allowed {
+        return ActionResult::blocked(verdict);
+    }
+    if verdict.requires_confirmation && !req.confirmed {
+        return ActionResult::needs_confirm(verdict);
+    }
+
+    match crate::platform::current().process_info(&target) {
+        Ok(json) => ActionResult::ok(json, verdict),
+        Err(e) => ActionResult::err(e, verdict),
     }
 }
 
 // ─── System Info ─────────────────────────────────────
 
 fn system_info() -> ActionResult {
-    let output = Command::new("cmd")
-        .args(["/C", "systeminfo | findstr /B /C:\"OS\" /C:\"System\" /C:\"Total Physical\" /C:\"Available Physical\" /C:\"Processor\""])
-        .output();
-
-    match output {
-        Ok(out) => ActionResult::ok(
-            String::from_utf8_lossy(&out.stdout).to_string(),
-            safe_verdict(),
-        ),
-        Err(e) => ActionResult::err(format!("Failed: {}", e), safe_verdict()),
+    match crate::platform::current().system_info() {
+        Ok(info) => {
+            let integrity = safety::current_integrity_level();
+            let restricted = if safety::is_token_restricted() { " (restricted)" } else { "" };
+            ActionResult::ok(
+                format!("{}\nIntegrity Level: {}{}", info, integrity.label(), restricted),
+                safe_verdict(),
+            )
+        }
+        Err(e) => ActionResult::err(e, safe_verdict()),
     }
 }
 
 fn disk_usage() -> ActionResult {
-    let output = Command::new("wmic")
-        .args(["logicaldisk", "get", "caption,freespace,size", "/format:csv"])
-        .output();
-
-    match output {
-        Ok(out) => ActionResult::ok(
-            String::from_utf8_lossy(&out.stdout).to_string(),
-            safe_verdict(),
-        ),
-        Err(e) => ActionResult::err(format!("Failed: {}", e), safe_verdict()),
+    match crate::platform::current().disk_usage() {
+        Ok(info) => ActionResult::ok(info, safe_verdict()),
+        Err(e) => ActionResult::err(e, safe_verdict()),
     }
 }
 
@@ -492,6 +864,19 @@ pub fn execute_desktop(params: &serde_json::Value) -> ActionResult {
     let y = params.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0) as i32;
     let button = params.get("button").and_then(|v| v.as_str()).unwrap_or("left");
     let delay = params.get("delay").and_then(|v| v.as_u64()).unwrap_or(0);
+    let occurrence = params.get("occurrence").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    let lang = params.get("lang").and_then(|v| v.as_str()).unwrap_or("");
+    let image_path = params.get("image_path").and_then(|v| v.as_str()).unwrap_or("");
+    let click = params.get("click").and_then(|v| v.as_bool()).unwrap_or(false);
+    let region = match (
+        params.get("region_x").and_then(|v| v.as_i64()),
+        params.get("region_y").and_then(|v| v.as_i64()),
+        params.get("region_w").and_then(|v| v.as_i64()),
+        params.get("region_h").and_then(|v| v.as_i64()),
+    ) {
+        (Some(rx), Some(ry), Some(rw), Some(rh)) => Some((rx as i32, ry as i32, rw as i32, rh as i32)),
+        _ => None,
+    };
 
     if action.is_empty() {
         return ActionResult::err("desktop action is required".into(), safe_verdict());
@@ -509,8 +894,11 @@ pub fn execute_desktop(params: &serde_json::Value) -> ActionResult {
         "send_keys" | "key_combo" => desktop_send_keys(text),
         "type_text" => desktop_type_text(text),
         "click" => desktop_click(x, y, button),
-        "screenshot" => desktop_screenshot(target),
-        "read_screen" => desktop_read_screen(target),
+        "find_and_click" => desktop_find_and_click(target, text, occurrence, button),
+        "screenshot" => desktop_screenshot(target, region),
+        "read_screen" => desktop_read_screen(target, region, lang),
+        "ocr_languages" => desktop_list_ocr_languages(),
+        "detect_faces" => desktop_detect_faces(target, region, image_path, click, button),
         "read_window_text" => desktop_read_window_text(target),
         "get_clipboard" => desktop_get_clipboard(),
         "wait" => {
@@ -678,30 +1066,101 @@ Write-Output "CLICKED: ({x}, {y}) {button}"
     run_powershell(&script)
 }
 
-fn desktop_screenshot(target: &str) -> ActionResult {
-    use base64::Engine;
+/// OCR the screen (or a named window), find the `occurrence`-th OCR word whose text
+/// contains `query` (case-insensitive), and click its bounding-box center. Only single words
+/// are searched, not whole lines, so a query spanning several words does not match.
+fn desktop_find_and_click(target: &str, query: &str, occurrence: usize, button: &str) -> ActionResult {
+    if query.is_empty() {
+        return ActionResult::err("text is required for find_and_click".into(), safe_verdict());
+    }
 
-    let dir = std::env::temp_dir().join("forgeai_screenshots");
-    let _ = std::fs::create_dir_all(&dir);
-    let filename = format!("screenshot_{}.png", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis());
-    let path = dir.join(&filename);
-    let path_str = path.to_string_lossy().replace('\\', "\\\\");
+    let read_result = desktop_read_screen(target, None, "");
+    if !read_result.success {
+        return read_result;
+    }
 
-    let script = if target.is_empty() {
-        // Full screen
+    let ocr: serde_json::Value = match serde_json::from_str(&read_result.output) {
+        Ok(v) => v,
+        Err(e) => return ActionResult::err(format!("Could not parse OCR output: {}", e), safe_verdict()),
+    };
+
+    let needle = query.to_lowercase();
+    let mut matches: Vec<(String, i64, i64, i64, i64)> = Vec::new();
+    if let Some(lines) = ocr.get("lines").and_then(|v| v.as_array()) {
+        for line in lines {
+            if let Some(words) = line.get("words").and_then(|v| v.as_array()) {
+                for word in words {
+                    let text = word.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                    if text.to_lowercase().contains(&needle) {
+                        matches.push((
+                            text.to_string(),
+                            word.get("x").and_then(|v| v.as_i64()).unwrap_or(0),
+                            word.get("y").and_then(|v| v.as_i64()).unwrap_or(0),
+                            word.get("w").and_then(|v| v.as_i64()).unwrap_or(0),
+                            word.get("h").and_then(|v| v.as_i64()).unwrap_or(0),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    let (matched_text, x, y, w, h) = match matches.get(occurrence) {
+        Some(m) => m.clone(),
+        None => {
+            return ActionResult::err(
+                format!("No match for '{}' (occurrence {}) — found {} total", query, occurrence, matches.len()),
+                safe_verdict(),
+            )
+        }
+    };
+
+    let cx = (x + w / 2) as i32;
+    let cy = (y + h / 2) as i32;
+    let click_result = desktop_click(cx, cy, button);
+    if !click_result.success {
+        return click_result;
+    }
+
+    let json_output = serde_json::json!({
+        "matched_text": matched_text,
+        "x": cx,
+        "y": cy,
+        "button": button,
+    });
+    ActionResult::ok(json_output.to_string(), safe_verdict())
+}
+
+/// Build the PowerShell fragment that captures the requested target into an in-memory
+/// GDI bitmap variable `$b` (region takes priority over a named window, which takes
+/// priority over the full screen), leaving `$ox`/`$oy` set to that bitmap's screen
+/// origin and `$found` set to whether the capture actually produced a bitmap.
+fn capture_fragment(target: &str, region: Option<(i32, i32, i32, i32)>) -> String {
+    if let Some((rx, ry, rw, rh)) = region {
+        // Scoped region of the full screen — mirrors the OcrArea(X,Y,W,H) pattern
         format!(r#"
 Add-Type -AssemblyName System.Windows.Forms; Add-Type -AssemblyName System.Drawing
+$ox={rx}; $oy={ry}
+$b=New-Object System.Drawing.Bitmap({rw},{rh})
+$g=[System.Drawing.Graphics]::FromImage($b)
+$g.CopyFromScreen({rx},{ry},0,0,[System.Drawing.Size]::new({rw},{rh}))
+$g.Dispose()
+$found=$true
+"#, rx=rx, ry=ry, rw=rw, rh=rh)
+    } else if target.is_empty() {
+        // Full screen
+        r#"
+Add-Type -AssemblyName System.Windows.Forms; Add-Type -AssemblyName System.Drawing
+$ox=0; $oy=0
 $s=[System.Windows.Forms.Screen]::PrimaryScreen.Bounds
 $b=New-Object System.Drawing.Bitmap($s.Width,$s.Height)
 $g=[System.Drawing.Graphics]::FromImage($b)
 $g.CopyFromScreen(0,0,0,0,[System.Drawing.Size]::new($s.Width,$s.Height))
-$b.Save("{path}")
-$g.Dispose(); $b.Dispose()
-Write-Output "SCREENSHOT: {path} ($($s.Width)x$($s.Height))"
-"#, path=path_str)
+$g.Dispose()
+$found=$true
+"#.to_string()
     } else {
-        // Window screenshot using PrintWindow
+        // Window capture via PrintWindow straight into an in-memory bitmap
         let safe = target.replace('\'', "''");
         format!(r#"
 Add-Type @"
@@ -719,7 +1178,7 @@ public class WinAPI {{
 }}
 "@
 Add-Type -AssemblyName System.Drawing
-$script:found=$false
+$ox=0; $oy=0; $found=$false
 [WinAPI]::EnumWindows({{ param($h,$l)
     if([WinAPI]::IsWindowVisible($h)) {{
         $t=[WinAPI]::GetTitle($h)
@@ -727,93 +1186,207 @@ $script:found=$false
             $r=New-Object WinAPI+RECT; [WinAPI]::GetWindowRect($h,[ref]$r)|Out-Null
             $w=$r.Right-$r.Left; $ht=$r.Bottom-$r.Top
             if($w -gt 0 -and $ht -gt 0) {{
-                $bmp=New-Object System.Drawing.Bitmap($w,$ht)
-                $g=[System.Drawing.Graphics]::FromImage($bmp)
+                $script:b=New-Object System.Drawing.Bitmap($w,$ht)
+                $g=[System.Drawing.Graphics]::FromImage($b)
                 $hdc=$g.GetHdc()
                 [WinAPI]::PrintWindow($h,$hdc,2)|Out-Null
                 $g.ReleaseHdc($hdc); $g.Dispose()
-                $bmp.Save("{path}"); $bmp.Dispose()
-                Write-Output "SCREENSHOT: {path} (${{w}}x${{ht}}) [window: $t]"
-                $script:found=$true; return $false
+                $script:ox=$r.Left; $script:oy=$r.Top; $script:found=$true
+                return $false
             }}
         }}
     }}; $true
 }}, [IntPtr]::Zero)|Out-Null
-if(-not $found) {{ Write-Output "NOT_FOUND: No window matching '*{safe}*'" }}
-"#, safe=safe, path=path_str)
-    };
-
-    let ps_result = run_powershell(&script);
-    if !ps_result.success {
-        return ps_result;
+"#, safe=safe)
     }
+}
 
-    // Read the PNG file and base64-encode it so the Gateway can save + display the image
-    let real_path = path.to_string_lossy().to_string();
-    match std::fs::read(&path) {
-        Ok(bytes) => {
-            let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
-            let json_output = serde_json::json!({
-                "output": ps_result.output.trim(),
-                "filename": filename,
-                "image_base64": b64,
-            });
-            ActionResult::ok(json_output.to_string(), safe_verdict())
-        }
-        Err(e) => {
-            // File not found — return the PS output anyway
-            log::warn!("[desktop_screenshot] Could not read {}: {}", real_path, e);
-            ps_result
-        }
-    }
+fn desktop_screenshot(target: &str, region: Option<(i32, i32, i32, i32)>) -> ActionResult {
+    // Capture straight into a GDI bitmap, PNG-encode into a MemoryStream, and base64
+    // the bytes from there — no temp file is ever written to disk.
+    let capture = capture_fragment(target, region);
+    let not_found_msg = format!("NOT_FOUND: No window matching '*{}*'", target.replace('\'', "''"));
+    let script = format!(r#"
+{capture}
+if(-not $found) {{ Write-Output '{{"error":"{not_found}"}}'; exit }}
+$ms=New-Object System.IO.MemoryStream
+$b.Save($ms,[System.Drawing.Imaging.ImageFormat]::Png)
+$bytes=$ms.ToArray()
+$b64=[Convert]::ToBase64String($bytes)
+[PSCustomObject]@{{ width=$b.Width; height=$b.Height; x=$ox; y=$oy; image_base64=$b64 }} | ConvertTo-Json -Compress
+$ms.Dispose(); $b.Dispose()
+"#, capture = capture, not_found = not_found_msg.replace('"', "'"));
+
+    run_powershell(&script)
 }
 
-fn desktop_read_screen(target: &str) -> ActionResult {
-    // Screenshot + OCR using Windows OCR API
-    let dir = std::env::temp_dir().join("forgeai_screenshots");
-    let _ = std::fs::create_dir_all(&dir);
-    let filename = format!("ocr_{}.png", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis());
-    let path = dir.join(&filename);
-    let path_str = path.to_string_lossy().replace('\\', "\\\\");
+/// Enumerate the BCP-47 language tags the host's Windows OCR install actually supports,
+/// so callers can discover valid `lang` values for `read_screen` before requesting one.
+fn desktop_list_ocr_languages() -> ActionResult {
+    let script = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$null=[Windows.Media.Ocr.OcrEngine,Windows.Foundation,ContentType=WindowsRuntime]
+$langs = [Windows.Media.Ocr.OcrEngine]::AvailableRecognizerLanguages | ForEach-Object {
+    [PSCustomObject]@{ tag = $_.LanguageTag; display_name = $_.DisplayName }
+}
+[PSCustomObject]@{ languages = $langs } | ConvertTo-Json -Compress -Depth 3
+"#;
+    run_powershell(script)
+}
 
-    // First take screenshot
-    let screenshot_result = if target.is_empty() {
-        desktop_screenshot("")
+fn desktop_read_screen(target: &str, region: Option<(i32, i32, i32, i32)>, lang: &str) -> ActionResult {
+    // Capture directly into a GDI bitmap and hand it to the OCR engine via an in-memory
+    // IRandomAccessStream (MemoryStream.AsRandomAccessStream()) — no PNG ever touches disk,
+    // so there's no temp file to clean up and no race waiting for a flush before OCR reads it.
+    let capture = capture_fragment(target, region);
+
+    // Pick the OCR engine: an explicit `lang` (BCP-47 tag) first, falling back through
+    // the user's profile languages and finally en-US if the requested pack isn't installed.
+    let engine_script = if lang.is_empty() {
+        r#"$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromUserProfileLanguages()
+    if(-not $e){$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromLanguage([Windows.Globalization.Language]::new("en-US"))}"#.to_string()
     } else {
-        desktop_screenshot(target)
+        let safe_lang = lang.replace('"', "").replace('\'', "");
+        format!(
+            r#"$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromLanguage([Windows.Globalization.Language]::new("{lang}"))
+    if(-not $e){{$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromUserProfileLanguages()}}
+    if(-not $e){{$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromLanguage([Windows.Globalization.Language]::new("en-US"))}}"#,
+            lang = safe_lang
+        )
     };
 
-    if !screenshot_result.success {
-        return screenshot_result;
-    }
-
-    // Now run OCR on the screenshot
     let ocr_script = format!(r#"
 Add-Type -AssemblyName System.Runtime.WindowsRuntime
 $null=[Windows.Media.Ocr.OcrEngine,Windows.Foundation,ContentType=WindowsRuntime]
 $null=[Windows.Graphics.Imaging.BitmapDecoder,Windows.Foundation,ContentType=WindowsRuntime]
+$asTaskGeneric=([System.WindowsRuntimeSystemExtensions].GetMethods()|Where-Object{{$_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'}})[0]
+Function AwaitOp($t,$r){{$task=$asTaskGeneric.MakeGenericMethod($r).Invoke($null,@($t));if(-not $task.Wait(20000)){{throw "timeout"}};$task.Result}}
+{capture}
+if(-not $found) {{ Write-Output '{{"error":"No window matching target"}}'; exit }}
+try {{
+    $ms=New-Object System.IO.MemoryStream
+    $b.Save($ms,[System.Drawing.Imaging.ImageFormat]::Png)
+    $ms.Position=0
+    $ras=[System.Runtime.InteropServices.WindowsRuntime.WindowsRuntimeStreamExtensions]::AsRandomAccessStream($ms)
+    $d=AwaitOp ([Windows.Graphics.Imaging.BitmapDecoder]::CreateAsync($ras)) ([Windows.Graphics.Imaging.BitmapDecoder])
+    $sb=AwaitOp ($d.GetSoftwareBitmapAsync()) ([Windows.Graphics.Imaging.SoftwareBitmap])
+    {engine_script}
+    if($e){{
+        $r=AwaitOp ($e.RecognizeAsync($sb)) ([Windows.Media.Ocr.OcrResult])
+        $lines=@()
+        foreach($ln in $r.Lines) {{
+            $words=@()
+            foreach($w in $ln.Words) {{
+                $br=$w.BoundingRect
+                $words += [PSCustomObject]@{{text=$w.Text; x=[int]($br.X+$ox); y=[int]($br.Y+$oy); w=[int]$br.Width; h=[int]$br.Height}}
+            }}
+            $lines += [PSCustomObject]@{{text=$ln.Text; words=$words}}
+        }}
+        [PSCustomObject]@{{lines=$lines}} | ConvertTo-Json -Compress -Depth 6
+    }}
+    else{{Write-Output '{{"error":"No OCR engine available"}}'}}
+    $ms.Dispose(); $b.Dispose()
+}} catch {{ Write-Output "{{`"error`":`"$($_.Exception.Message)`"}}" }}
+"#, capture = capture, engine_script = engine_script);
+
+    run_powershell(&ocr_script)
+}
+
+/// Detect faces via `Windows.Media.FaceAnalysis.FaceDetector`, either in a decoded image
+/// file (`image_path`) or in a fresh screen/window/region capture. With `click`, moves the
+/// cursor to and clicks the face nearest the capture's center, mirroring `find_and_click`.
+fn desktop_detect_faces(target: &str, region: Option<(i32, i32, i32, i32)>, image_path: &str, click: bool, button: &str) -> ActionResult {
+    let source_script = if !image_path.is_empty() {
+        let safe_path = image_path.replace('\\', "\\\\").replace('\'', "''");
+        format!(r#"
+$ox=0; $oy=0; $found=$true
+$f=AwaitOp ([Windows.Storage.StorageFile]::GetFileFromPathAsync('{path}')) ([Windows.Storage.StorageFile])
+$s=AwaitOp ($f.OpenAsync([Windows.Storage.FileAccessMode]::Read)) ([Windows.Storage.Streams.IRandomAccessStream])
+$d=AwaitOp ([Windows.Graphics.Imaging.BitmapDecoder]::CreateAsync($s)) ([Windows.Graphics.Imaging.BitmapDecoder])
+$sb=AwaitOp ($d.GetSoftwareBitmapAsync()) ([Windows.Graphics.Imaging.SoftwareBitmap])
+$capW=$sb.PixelWidth; $capH=$sb.PixelHeight
+"#, path = safe_path)
+    } else {
+        let capture = capture_fragment(target, region);
+        format!(r#"
+{capture}
+if(-not $found) {{ Write-Output '{{"error":"No window matching target"}}'; exit }}
+$capW=$b.Width; $capH=$b.Height
+$ms=New-Object System.IO.MemoryStream
+$b.Save($ms,[System.Drawing.Imaging.ImageFormat]::Png)
+$ms.Position=0
+$ras=[System.Runtime.InteropServices.WindowsRuntime.WindowsRuntimeStreamExtensions]::AsRandomAccessStream($ms)
+$d=AwaitOp ([Windows.Graphics.Imaging.BitmapDecoder]::CreateAsync($ras)) ([Windows.Graphics.Imaging.BitmapDecoder])
+$sb=AwaitOp ($d.GetSoftwareBitmapAsync()) ([Windows.Graphics.Imaging.SoftwareBitmap])
+$ms.Dispose(); $b.Dispose()
+"#, capture = capture)
+    };
+
+    let script = format!(r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+Add-Type -AssemblyName System.Windows.Forms; Add-Type -AssemblyName System.Drawing
+$null=[Windows.Media.FaceAnalysis.FaceDetector,Windows.Foundation,ContentType=WindowsRuntime]
+$null=[Windows.Graphics.Imaging.BitmapDecoder,Windows.Foundation,ContentType=WindowsRuntime]
 $null=[Windows.Storage.StorageFile,Windows.Foundation,ContentType=WindowsRuntime]
 $asTaskGeneric=([System.WindowsRuntimeSystemExtensions].GetMethods()|Where-Object{{$_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'}})[0]
 Function AwaitOp($t,$r){{$task=$asTaskGeneric.MakeGenericMethod($r).Invoke($null,@($t));if(-not $task.Wait(20000)){{throw "timeout"}};$task.Result}}
 try {{
-    $f=AwaitOp ([Windows.Storage.StorageFile]::GetFileFromPathAsync('{path}')) ([Windows.Storage.StorageFile])
-    $s=AwaitOp ($f.OpenAsync([Windows.Storage.FileAccessMode]::Read)) ([Windows.Storage.Streams.IRandomAccessStream])
-    $d=AwaitOp ([Windows.Graphics.Imaging.BitmapDecoder]::CreateAsync($s)) ([Windows.Graphics.Imaging.BitmapDecoder])
-    $b=AwaitOp ($d.GetSoftwareBitmapAsync()) ([Windows.Graphics.Imaging.SoftwareBitmap])
-    $e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromUserProfileLanguages()
-    if(-not $e){{$e=[Windows.Media.Ocr.OcrEngine]::TryCreateFromLanguage([Windows.Globalization.Language]::new("en-US"))}}
-    if($e){{$r=AwaitOp ($e.RecognizeAsync($b)) ([Windows.Media.Ocr.OcrResult]); Write-Output $r.Text}}
-    else{{Write-Output "OCR_ERROR: No OCR engine available"}}
-}} catch {{ Write-Output "OCR_ERROR: $($_.Exception.Message)" }}
-"#, path=path_str);
-
-    let ocr_result = run_powershell(&ocr_script);
-    // Combine screenshot path and OCR text
-    ActionResult::ok(
-        format!("screenshot={}\ntext:{}", path_str, ocr_result.output),
-        safe_verdict(),
-    )
+    {source_script}
+    $gray=[Windows.Graphics.Imaging.SoftwareBitmap]::Convert($sb,[Windows.Graphics.Imaging.BitmapPixelFormat]::Gray8)
+    $fd=AwaitOp ([Windows.Media.FaceAnalysis.FaceDetector]::CreateAsync()) ([Windows.Media.FaceAnalysis.FaceDetector])
+    $faces=AwaitOp ($fd.DetectFacesAsync($gray)) ([System.Collections.Generic.IList`1[Windows.Media.FaceAnalysis.DetectedFace]])
+    $results=@()
+    foreach($f in $faces) {{
+        $bx=$f.FaceBox
+        $results += [PSCustomObject]@{{x=[int]($bx.X+$ox); y=[int]($bx.Y+$oy); w=[int]$bx.Width; h=[int]$bx.Height}}
+    }}
+    [PSCustomObject]@{{faces=$results; capture_x=$ox; capture_y=$oy; capture_w=$capW; capture_h=$capH}} | ConvertTo-Json -Compress -Depth 4
+}} catch {{ Write-Output "{{`"error`":`"$($_.Exception.Message)`"}}" }}
+"#, source_script = source_script);
+
+    let result = run_powershell(&script);
+    if !click || !result.success {
+        return result;
+    }
+
+    let parsed: serde_json::Value = match serde_json::from_str(&result.output) {
+        Ok(v) => v,
+        Err(_) => return result,
+    };
+    let faces = match parsed.get("faces").and_then(|v| v.as_array()) {
+        Some(f) if !f.is_empty() => f,
+        _ => return ActionResult::err("No faces detected".into(), safe_verdict()),
+    };
+    let capture_w = parsed.get("capture_w").and_then(|v| v.as_i64()).unwrap_or(0);
+    let capture_h = parsed.get("capture_h").and_then(|v| v.as_i64()).unwrap_or(0);
+    // Face boxes already carry the capture's screen offset, so the center must too.
+    let center_x = parsed.get("capture_x").and_then(|v| v.as_i64()).unwrap_or(0) + capture_w / 2;
+    let center_y = parsed.get("capture_y").and_then(|v| v.as_i64()).unwrap_or(0) + capture_h / 2;
+
+    let nearest = faces.iter().min_by_key(|f| {
+        let fx = f.get("x").and_then(|v| v.as_i64()).unwrap_or(0) + f.get("w").and_then(|v| v.as_i64()).unwrap_or(0) / 2;
+        let fy = f.get("y").and_then(|v| v.as_i64()).unwrap_or(0) + f.get("h").and_then(|v| v.as_i64()).unwrap_or(0) / 2;
+        (fx - center_x).pow(2) + (fy - center_y).pow(2)
+    });
+
+    let face = match nearest {
+        Some(f) => f,
+        None => return ActionResult::err("No faces detected".into(), safe_verdict()),
+    };
+    let fx = face.get("x").and_then(|v| v.as_i64()).unwrap_or(0);
+    let fy = face.get("y").and_then(|v| v.as_i64()).unwrap_or(0);
+    let fw = face.get("w").and_then(|v| v.as_i64()).unwrap_or(0);
+    let fh = face.get("h").and_then(|v| v.as_i64()).unwrap_or(0);
+    let cx = (fx + fw / 2) as i32;
+    let cy = (fy + fh / 2) as i32;
+
+    let click_result = desktop_click(cx, cy, button);
+    if !click_result.success {
+        return click_result;
+    }
+
+    let json_output = serde_json::json!({ "x": cx, "y": cy, "button": button });
+    ActionResult::ok(json_output.to_string(), safe_verdict())
 }
 
 fn desktop_read_window_text(target: &str) -> ActionResult {
@@ -853,6 +1426,7 @@ fn safe_verdict() -> SafetyVerdict {
         risk: RiskLevel::Safe,
         reason: String::new(),
         requires_confirmation: false,
+        requires_elevation: false,
     }
 }
 
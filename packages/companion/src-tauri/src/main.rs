@@ -5,10 +5,17 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod artifacts;
+mod audit;
 mod commands;
 mod connection;
 mod local_actions;
+mod metrics;
+mod platform;
+mod process;
+mod rate_limit;
 mod safety;
+mod tls;
 mod voice;
 mod wake_word;
 
@@ -28,6 +35,9 @@ fn main() {
         .manage(commands::VoiceState(std::sync::Mutex::new(
             voice::VoiceEngine::new(),
         )))
+        .manage(commands::ProcessState(std::sync::Mutex::new(
+            process::ProcessRegistry::new(),
+        )))
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             // Second instance tried to launch — focus existing window instead
             if let Some(window) = app.get_webview_window("main") {
@@ -45,6 +55,9 @@ fn main() {
         .plugin(tauri_plugin_os::init())
         .invoke_handler(tauri::generate_handler![
             commands::execute_action,
+            commands::execute_action_streaming,
+            commands::execute_pipeline,
+            commands::respond_to_action_approval,
             commands::check_safety,
             commands::get_safety_prompt,
             commands::get_status,
@@ -63,15 +76,33 @@ fn main() {
             commands::wake_word_status,
             commands::wake_word_configure,
             commands::voice_record,
+            commands::voice_record_live,
             commands::voice_stop,
             commands::voice_speak,
+            commands::voice_configure_tts,
+            commands::list_tts_voices,
+            commands::voice_configure_denoise,
+            commands::voice_transcribe,
+            commands::voice_record_and_transcribe,
+            commands::proc_spawn,
+            commands::proc_write_stdin,
+            commands::proc_resize,
+            commands::proc_kill,
+            commands::fetch_artifact,
             commands::read_screenshot,
             commands::list_sessions,
             commands::get_session_history,
             commands::delete_session,
+            commands::audit_query,
+            commands::audit_export,
+            commands::metrics_configure,
+            commands::metrics_snapshot,
             commands::list_audio_devices,
+            commands::set_input_device,
             commands::connect_gateway_ws,
             commands::force_reconnect_gateway_ws,
+            commands::voice_stream_start,
+            commands::voice_stream_stop,
         ])
         .setup(|app| {
             // ─── System Tray ───
@@ -97,6 +128,11 @@ fn main() {
                         }
                     }
                     "quit" => {
+                        if let Some(state) = app.try_state::<commands::ProcessState>() {
+                            if let Ok(registry) = state.0.lock() {
+                                registry.kill_all();
+                            }
+                        }
                         app.exit(0);
                     }
                     _ => {}
@@ -120,7 +156,11 @@ fn main() {
             log::info!("ForgeAI Companion started — system tray active");
 
             // Auto-connect Gateway WS if credentials exist
-            commands::spawn_gateway_ws();
+            commands::spawn_gateway_ws(app.handle().clone());
+
+            // Metrics exporter is always spawned but only pushes once a user opts in via
+            // `metrics_configure` — see `metrics::spawn_exporter`.
+            metrics::spawn_exporter();
 
             Ok(())
         })
@@ -0,0 +1,131 @@
+//! # Opt-In Metrics Exporter
+//!
+//! Tracks a handful of operational counters — wake-word detections, time spent in each
+//! `ConnectionState`, reconnect attempts, and action-request success/failure tallies by type —
+//! and, when enabled, periodically POSTs a JSON snapshot to a user-configured endpoint. Disabled
+//! by default: a privacy-conscious user never sends anything unless they explicitly `configure`
+//! an endpoint. Counters are still recorded while disabled so `snapshot()` reflects reality the
+//! moment metrics are turned on; the exporter task runs from startup but only pushes while enabled.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// User-configured metrics behavior. `enabled: false` (the default) means counters are still
+/// updated locally but nothing is ever sent over the network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+    pub push_interval_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { enabled: false, endpoint: None, push_interval_secs: 60 }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    wake_word_detections: u64,
+    reconnect_attempts: u64,
+    /// Seconds spent in each `ConnectionState` variant, keyed by its `Debug` label (`Error(..)`
+    /// is collapsed to `"Error"` so a changing error message doesn't fragment the map).
+    state_seconds: HashMap<String, u64>,
+    /// (action, success) -> count
+    action_tallies: HashMap<(String, bool), u64>,
+}
+
+static CONFIG: OnceLock<Mutex<MetricsConfig>> = OnceLock::new();
+static COUNTERS: OnceLock<Mutex<Counters>> = OnceLock::new();
+
+fn config() -> &'static Mutex<MetricsConfig> {
+    CONFIG.get_or_init(|| Mutex::new(MetricsConfig::default()))
+}
+
+fn counters() -> &'static Mutex<Counters> {
+    COUNTERS.get_or_init(|| Mutex::new(Counters::default()))
+}
+
+/// Update metrics behavior — mirrors `voice::VoiceEngine::configure_tts`'s "set config, take
+/// effect going forward" shape.
+pub fn configure(new_config: MetricsConfig) {
+    *config().lock().unwrap() = new_config;
+}
+
+pub fn is_enabled() -> bool {
+    config().lock().unwrap().enabled
+}
+
+/// Called from `wake_word::run_detection_loop` on every "Hey Forge" detection.
+pub fn record_wake_word_detection() {
+    counters().lock().unwrap().wake_word_detections += 1;
+}
+
+/// Called from `commands::gateway_ws_loop` each time it's about to retry the connection.
+pub fn record_reconnect_attempt() {
+    counters().lock().unwrap().reconnect_attempts += 1;
+}
+
+/// Called from the Gateway receive task whenever a dispatched action finishes.
+pub fn record_action_result(action: &str, success: bool) {
+    let mut c = counters().lock().unwrap();
+    *c.action_tallies.entry((action.to_string(), success)).or_insert(0) += 1;
+}
+
+/// Accumulate time spent in a `ConnectionState` before transitioning away from it. Callers pass
+/// the duration already spent in `from_state` when they observe a transition.
+pub fn record_connection_state_duration(state_label: &str, seconds: u64) {
+    if seconds == 0 {
+        return;
+    }
+    let mut c = counters().lock().unwrap();
+    *c.state_seconds.entry(state_label.to_string()).or_insert(0) += seconds;
+}
+
+/// Build the current counters as a JSON snapshot for export.
+pub fn snapshot() -> serde_json::Value {
+    let c = counters().lock().unwrap();
+    let actions: Vec<serde_json::Value> = c
+        .action_tallies
+        .iter()
+        .map(|((action, success), count)| {
+            serde_json::json!({ "action": action, "success": success, "count": count })
+        })
+        .collect();
+    serde_json::json!({
+        "wake_word_detections": c.wake_word_detections,
+        "reconnect_attempts": c.reconnect_attempts,
+        "connection_state_seconds": c.state_seconds,
+        "action_results": actions,
+    })
+}
+
+/// Spawn the background exporter task. Safe to call unconditionally at startup — the task pushes
+/// nothing unless `configure` has turned metrics on with an endpoint set. Re-reads
+/// the config on every tick, so toggling `enabled` off at runtime stops the next push without
+/// needing to restart the companion.
+pub fn spawn_exporter() {
+    tokio::spawn(async move {
+        loop {
+            let (enabled, endpoint, interval) = {
+                let cfg = config().lock().unwrap();
+                (cfg.enabled, cfg.endpoint.clone(), cfg.push_interval_secs.max(1))
+            };
+
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+
+            if !enabled {
+                continue;
+            }
+            let Some(endpoint) = endpoint else { continue };
+
+            let body = snapshot();
+            let client = reqwest::Client::new();
+            if let Err(e) = client.post(&endpoint).json(&body).send().await {
+                log::warn!("Metrics push to {} failed: {}", endpoint, e);
+            }
+        }
+    });
+}
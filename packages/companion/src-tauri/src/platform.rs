@@ -0,0 +1,357 @@
+//! # Platform Backend Abstraction
+//!
+//! `local_actions` hardcoded Windows tooling (`powershell.exe`, `tasklist`, `taskkill`,
+//! `wmic`, `cmd /C start`) for every action that shells out to the OS. This module pulls
+//! that OS-specific surface behind a `PlatformBackend` trait so `execute` stays identical
+//! across platforms — only the backend selected via `#[cfg(windows)]` / `#[cfg(unix)]`
+//! changes. The pure-filesystem actions in `local_actions` (`read_file`, `write_file`, ...)
+//! already go through `std::fs` and don't need a backend.
+
+use std::path::Path;
+use std::process::Command;
+
+/// The handful of OS-specific operations `local_actions` needs to shell out for.
+pub trait PlatformBackend: Sync {
+    /// Run a shell command, optionally in `cwd`. Returns (stdout, stderr).
+    fn run_shell(&self, command: &str, cwd: Option<&Path>) -> Result<(String, String), String>;
+    /// List running processes as human-readable lines.
+    fn list_processes(&self) -> Result<String, String>;
+    /// Terminate a process by name, returning whatever the OS tool printed.
+    fn kill_process(&self, name: &str) -> Result<String, String>;
+    /// Launch an application by name/path.
+    fn open_app(&self, app: &str) -> Result<(), String>;
+    /// Open a URL in the default browser.
+    fn open_url(&self, url: &str) -> Result<(), String>;
+    /// Human-readable OS/hardware summary.
+    fn system_info(&self) -> Result<String, String>;
+    /// Human-readable disk usage summary.
+    fn disk_usage(&self) -> Result<String, String>;
+    /// Structured diagnostics (memory, threads, handles, parent pid, runtime) for one process,
+    /// targeted by name or numeric PID. Returns a JSON object as a string.
+    fn process_info(&self, target: &str) -> Result<String, String>;
+    /// Like `run_shell`, but invokes `on_line("stdout" | "stderr", line)` as each line of
+    /// output arrives instead of buffering everything until the command exits. Still returns
+    /// the (stdout, stderr) text, decoded lossily as `run_shell` does but with every line ending
+    /// in `\n`, plus the process exit code.
+    fn run_shell_streaming(
+        &self,
+        command: &str,
+        cwd: Option<&Path>,
+        on_line: &mut dyn FnMut(&str, &str),
+    ) -> Result<(String, String, Option<i32>), String>;
+}
+
+/// The backend for the platform this binary was compiled for.
+pub fn current() -> &'static dyn PlatformBackend {
+    #[cfg(windows)]
+    {
+        static BACKEND: WindowsBackend = WindowsBackend;
+        &BACKEND
+    }
+    #[cfg(unix)]
+    {
+        static BACKEND: UnixBackend = UnixBackend;
+        &BACKEND
+    }
+}
+
+/// Run `cmd` and collect its stdout/stderr as lossy UTF-8.
+fn run(mut cmd: Command) -> Result<(String, String), String> {
+    let out = cmd.output().map_err(|e| format!("Failed to execute: {}", e))?;
+    Ok((
+        String::from_utf8_lossy(&out.stdout).to_string(),
+        String::from_utf8_lossy(&out.stderr).to_string(),
+    ))
+}
+
+/// Spawn `cmd` with piped stdout/stderr, calling `on_line` as each line arrives on either
+/// stream. Reader threads feed a single channel so lines are delivered in arrival order
+/// regardless of which stream produced them. Returns each stream's text, decoded lossily as `run`
+/// does but with every line ending in `\n`, plus the process exit code.
+fn run_streaming(
+    mut cmd: Command,
+    on_line: &mut dyn FnMut(&str, &str),
+) -> Result<(String, String, Option<i32>), String> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to execute: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    let (tx, rx) = std::sync::mpsc::channel::<(&'static str, String)>();
+
+    let stdout_tx = tx.clone();
+    let stdout_handle = std::thread::spawn(move || {
+        for bytes in BufReader::new(stdout).split(b'\n').map_while(Result::ok) {
+            let line = String::from_utf8_lossy(&bytes).trim_end_matches('\r').to_string();
+            if stdout_tx.send(("stdout", line)).is_err() {
+                break;
+            }
+        }
+    });
+    let stderr_handle = std::thread::spawn(move || {
+        for bytes in BufReader::new(stderr).split(b'\n').map_while(Result::ok) {
+            let line = String::from_utf8_lossy(&bytes).trim_end_matches('\r').to_string();
+            if tx.send(("stderr", line)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut stdout_buf = String::new();
+    let mut stderr_buf = String::new();
+    while let Ok((stream, line)) = rx.recv() {
+        on_line(stream, &line);
+        let buf = if stream == "stdout" { &mut stdout_buf } else { &mut stderr_buf };
+        buf.push_str(&line);
+        buf.push('\n');
+    }
+
+    let _ = stdout_handle.join();
+    let _ = stderr_handle.join();
+    let status = child.wait().map_err(|e| format!("Failed to wait on child: {}", e))?;
+
+    Ok((stdout_buf, stderr_buf, status.code()))
+}
+
+// ─── Windows ──────────────────────────────────────────
+
+#[cfg(windows)]
+pub struct WindowsBackend;
+
+#[cfg(windows)]
+impl PlatformBackend for WindowsBackend {
+    fn run_shell(&self, command: &str, cwd: Option<&Path>) -> Result<(String, String), String> {
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command", command]);
+        if let Some(dir) = cwd {
+            if dir.exists() {
+                cmd.current_dir(dir);
+            }
+        }
+        run(cmd)
+    }
+
+    fn list_processes(&self) -> Result<String, String> {
+        let mut cmd = Command::new("tasklist");
+        cmd.args(["/FO", "CSV", "/NH"]);
+        let (stdout, _stderr) = run(cmd)?;
+        let lines: Vec<&str> = stdout.lines().take(50).collect();
+        Ok(format!("Top 50 processes:\n{}", lines.join("\n")))
+    }
+
+    fn kill_process(&self, name: &str) -> Result<String, String> {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/IM", name, "/F"]);
+        let (stdout, _stderr) = run(cmd)?;
+        Ok(stdout)
+    }
+
+    fn open_app(&self, app: &str) -> Result<(), String> {
+        Command::new("cmd")
+            .args(["/C", "start", "", app])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open {}: {}", app, e))
+    }
+
+    fn open_url(&self, url: &str) -> Result<(), String> {
+        Command::new("cmd")
+            .args(["/C", "start", "", url])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open {}: {}", url, e))
+    }
+
+    fn system_info(&self) -> Result<String, String> {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "systeminfo | findstr /B /C:\"OS\" /C:\"System\" /C:\"Total Physical\" /C:\"Available Physical\" /C:\"Processor\""]);
+        let (stdout, _stderr) = run(cmd)?;
+        Ok(stdout)
+    }
+
+    fn disk_usage(&self) -> Result<String, String> {
+        let mut cmd = Command::new("wmic");
+        cmd.args(["logicaldisk", "get", "caption,freespace,size", "/format:csv"]);
+        let (stdout, _stderr) = run(cmd)?;
+        Ok(stdout)
+    }
+
+    fn process_info(&self, target: &str) -> Result<String, String> {
+        let selector = if !target.is_empty() && target.chars().all(|c| c.is_ascii_digit()) {
+            format!("-Id {}", target)
+        } else {
+            format!("-Name '{}'", target.replace('\'', "''"))
+        };
+        let script = format!(
+            r#"
+$p = Get-Process {selector} -ErrorAction Stop | Select-Object -First 1
+$wmi = Get-CimInstance Win32_Process -Filter "ProcessId=$($p.Id)" -ErrorAction SilentlyContinue
+$elapsed = if ($p.StartTime) {{ (Get-Date) - $p.StartTime }} else {{ $null }}
+[PSCustomObject]@{{
+    pid = $p.Id
+    name = $p.ProcessName
+    parent_pid = if ($wmi) {{ $wmi.ParentProcessId }} else {{ $null }}
+    working_set_bytes = $p.WorkingSet64
+    private_memory_bytes = $p.PrivateMemorySize64
+    thread_count = $p.Threads.Count
+    handle_count = $p.HandleCount
+    start_time = if ($p.StartTime) {{ $p.StartTime.ToString("o") }} else {{ $null }}
+    cpu_seconds = $p.CPU
+    elapsed_seconds = if ($elapsed) {{ $elapsed.TotalSeconds }} else {{ $null }}
+}} | ConvertTo-Json -Compress
+"#,
+            selector = selector
+        );
+
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        let (stdout, stderr) = run(cmd)?;
+        let trimmed = stdout.trim();
+        if trimmed.is_empty() {
+            return Err(format!("No process found matching '{}': {}", target, stderr.trim()));
+        }
+        Ok(trimmed.to_string())
+    }
+
+    fn run_shell_streaming(
+        &self,
+        command: &str,
+        cwd: Option<&Path>,
+        on_line: &mut dyn FnMut(&str, &str),
+    ) -> Result<(String, String, Option<i32>), String> {
+        let mut cmd = Command::new("powershell.exe");
+        cmd.args(["-NoProfile", "-NonInteractive", "-Command", command]);
+        if let Some(dir) = cwd {
+            if dir.exists() {
+                cmd.current_dir(dir);
+            }
+        }
+        run_streaming(cmd, on_line)
+    }
+}
+
+// ─── Unix (Linux + macOS) ─────────────────────────────
+
+#[cfg(unix)]
+pub struct UnixBackend;
+
+#[cfg(unix)]
+impl PlatformBackend for UnixBackend {
+    fn run_shell(&self, command: &str, cwd: Option<&Path>) -> Result<(String, String), String> {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", command]);
+        if let Some(dir) = cwd {
+            if dir.exists() {
+                cmd.current_dir(dir);
+            }
+        }
+        run(cmd)
+    }
+
+    fn list_processes(&self) -> Result<String, String> {
+        let mut cmd = Command::new("ps");
+        cmd.arg("aux");
+        let (stdout, _stderr) = run(cmd)?;
+        let lines: Vec<&str> = stdout.lines().take(50).collect();
+        Ok(format!("Top 50 processes:\n{}", lines.join("\n")))
+    }
+
+    fn kill_process(&self, name: &str) -> Result<String, String> {
+        let mut cmd = Command::new("pkill");
+        cmd.args(["-9", name]);
+        let (stdout, stderr) = run(cmd)?;
+        if stdout.is_empty() && stderr.is_empty() {
+            Ok(format!("Sent SIGKILL to processes matching '{}'", name))
+        } else {
+            Ok(format!("{}{}", stdout, stderr))
+        }
+    }
+
+    fn open_app(&self, app: &str) -> Result<(), String> {
+        let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+        Command::new(opener)
+            .arg(app)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open {}: {}", app, e))
+    }
+
+    fn open_url(&self, url: &str) -> Result<(), String> {
+        self.open_app(url)
+    }
+
+    fn system_info(&self) -> Result<String, String> {
+        let mut cmd = Command::new("uname");
+        cmd.arg("-a");
+        let (stdout, _stderr) = run(cmd)?;
+        Ok(stdout)
+    }
+
+    fn disk_usage(&self) -> Result<String, String> {
+        let mut cmd = Command::new("df");
+        cmd.arg("-h");
+        let (stdout, _stderr) = run(cmd)?;
+        Ok(stdout)
+    }
+
+    fn process_info(&self, target: &str) -> Result<String, String> {
+        let pid = if !target.is_empty() && target.chars().all(|c| c.is_ascii_digit()) {
+            target.to_string()
+        } else {
+            let mut pgrep = Command::new("pgrep");
+            pgrep.args(["-n", "-x", target]);
+            let (stdout, _stderr) = run(pgrep)?;
+            let found = stdout.trim();
+            if found.is_empty() {
+                return Err(format!("No process found matching '{}'", target));
+            }
+            found.to_string()
+        };
+
+        let mut cmd = Command::new("ps");
+        cmd.args(["-o", "pid=,ppid=,comm=,etimes=,time=,nlwp=,rss=,vsz=", "-p", &pid]);
+        let (stdout, _stderr) = run(cmd)?;
+        let line = stdout.trim();
+        if line.is_empty() {
+            return Err(format!("Process {} not found", pid));
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 {
+            return Err(format!("Unexpected ps output: {}", line));
+        }
+
+        let json = serde_json::json!({
+            "pid": fields[0].parse::<i64>().unwrap_or(0),
+            "parent_pid": fields[1].parse::<i64>().unwrap_or(0),
+            "name": fields[2],
+            "elapsed_seconds": fields[3].parse::<i64>().unwrap_or(0),
+            "cpu_time": fields[4],
+            "thread_count": fields[5].parse::<i64>().unwrap_or(0),
+            "rss_kb": fields[6].parse::<i64>().unwrap_or(0),
+            "vsz_kb": fields[7].parse::<i64>().unwrap_or(0),
+        });
+        Ok(json.to_string())
+    }
+
+    fn run_shell_streaming(
+        &self,
+        command: &str,
+        cwd: Option<&Path>,
+        on_line: &mut dyn FnMut(&str, &str),
+    ) -> Result<(String, String, Option<i32>), String> {
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", command]);
+        if let Some(dir) = cwd {
+            if dir.exists() {
+                cmd.current_dir(dir);
+            }
+        }
+        run_streaming(cmd, on_line)
+    }
+}
@@ -0,0 +1,171 @@
+//! # Interactive Process Sessions
+//!
+//! `local_actions::run_shell`/`run_shell_streaming` are fire-and-forget: they launch a command
+//! and either wait for it to exit or stream its output, but there's no way to talk back to it.
+//! This module adds long-lived, two-way process sessions — build scripts you want to watch, log
+//! tails, REPLs — launched under a pseudo-terminal where the platform has one so interactive
+//! programs (shells, `npm run dev`, anything that checks `isatty`) behave the same as they would
+//! in a real terminal. Each session is tracked in a `ProcessRegistry` (mirroring
+//! `WakeWordState`/`VoiceState`'s one-registry-in-managed-state shape) and reaped on Gateway
+//! reconnect or app shutdown so a killed connection can't leave orphaned children running.
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// One live interactive process.
+struct ProcessHandle {
+    writer: Box<dyn Write + Send>,
+    master: Box<dyn portable_pty::MasterPty + Send>,
+    killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
+}
+
+/// Managed-state registry of live interactive process sessions, keyed by a per-process id
+/// assigned on spawn.
+pub struct ProcessRegistry {
+    next_id: AtomicU64,
+    processes: Mutex<HashMap<u64, ProcessHandle>>,
+}
+
+impl ProcessRegistry {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            processes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Launch `command` (with `args`) under a pseudo-terminal, optionally in `cwd`. Spawns a
+    /// reader thread that emits each output chunk as a `process-output` event tagged by the
+    /// returned id, and a `process-exit` event once the child exits.
+    pub fn spawn(
+        &self,
+        app_handle: tauri::AppHandle,
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+    ) -> Result<u64, String> {
+        use tauri::Emitter;
+
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to open pseudo-terminal: {}", e))?;
+
+        let mut cmd = CommandBuilder::new(&command);
+        cmd.args(&args);
+        if let Some(dir) = &cwd {
+            cmd.cwd(dir);
+        }
+
+        let mut child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| format!("Failed to spawn {}: {}", command, e))?;
+        let killer = child.clone_killer();
+
+        // The slave side is only needed to spawn the child; drop our end so EOF propagates
+        // correctly to the reader once the child exits.
+        drop(pair.slave);
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| format!("Failed to clone PTY reader: {}", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| format!("Failed to take PTY writer: {}", e))?;
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        log::info!("Process {}: spawned `{} {:?}`", id, command, args);
+
+        std::thread::spawn(move || {
+            use std::io::Read;
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let chunk = String::from_utf8_lossy(&buf[..n]).to_string();
+                        let _ = app_handle.emit(
+                            "process-output",
+                            serde_json::json!({ "id": id, "data": chunk }),
+                        );
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            let exit_code: Option<i32> = child
+                .wait()
+                .ok()
+                .and_then(|status| status.exit_code().try_into().ok());
+            let _ = app_handle.emit(
+                "process-exit",
+                serde_json::json!({ "id": id, "exitCode": exit_code }),
+            );
+            log::info!("Process {}: exited (code={:?})", id, exit_code);
+        });
+
+        self.processes.lock().unwrap().insert(
+            id,
+            ProcessHandle {
+                writer,
+                master: pair.master,
+                killer,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Write raw bytes to a process's stdin (its PTY, really — so e.g. Ctrl-C works).
+    pub fn write_stdin(&self, id: u64, data: &[u8]) -> Result<(), String> {
+        let mut processes = self.processes.lock().unwrap();
+        let handle = processes.get_mut(&id).ok_or("No such process")?;
+        handle
+            .writer
+            .write_all(data)
+            .map_err(|e| format!("Write to process {} failed: {}", id, e))
+    }
+
+    /// Resize a process's pseudo-terminal to match the frontend's terminal widget.
+    pub fn resize(&self, id: u64, cols: u16, rows: u16) -> Result<(), String> {
+        let processes = self.processes.lock().unwrap();
+        let handle = processes.get(&id).ok_or("No such process")?;
+        handle
+            .master
+            .resize(PtySize { rows, cols, pixel_width: 0, pixel_height: 0 })
+            .map_err(|e| format!("Resize of process {} failed: {}", id, e))
+    }
+
+    /// Forcibly terminate a process and drop it from the registry.
+    pub fn kill(&self, id: u64) -> Result<(), String> {
+        let mut processes = self.processes.lock().unwrap();
+        let mut handle = processes.remove(&id).ok_or("No such process")?;
+        handle
+            .killer
+            .kill()
+            .map_err(|e| format!("Kill of process {} failed: {}", id, e))
+    }
+
+    /// Terminate every live process — called on Gateway disconnect/reconnect and app shutdown so
+    /// a torn-down remote-execution channel can't leave orphaned children running.
+    pub fn kill_all(&self) {
+        let mut processes = self.processes.lock().unwrap();
+        for (id, mut handle) in processes.drain() {
+            if let Err(e) = handle.killer.kill() {
+                log::warn!("Process {}: kill on teardown failed: {}", id, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! # Per-Action Rate Limiting
+//!
+//! Throttles inbound `action_request`s from the Gateway so a misbehaving or compromised Gateway
+//! can't launch unbounded concurrent shell commands or file operations. Each `ActionCategory`
+//! gets its own token bucket (request rate) and semaphore (concurrent executions); exhausting
+//! either rejects the request immediately instead of queuing it — callers report this back as a
+//! `rate_limited` `action_result` with a `retryAfterMs` hint.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Categories of inbound actions, each throttled independently. Defaults are conservative for
+/// high-risk categories (shell/process) and looser for read-only ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionCategory {
+    Shell,
+    FileWrite,
+    Desktop,
+    Process,
+    ReadOnly,
+}
+
+impl ActionCategory {
+    /// Classify an inbound `action_request`'s `action` field.
+    pub fn classify(action: &str) -> Self {
+        match action {
+            "shell" => Self::Shell,
+            "desktop" => Self::Desktop,
+            "kill_process" | "open_app" => Self::Process,
+            "write_file" | "delete_file" | "move_file" | "copy_file" | "create_dir" => Self::FileWrite,
+            _ => Self::ReadOnly,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Shell => "shell",
+            Self::FileWrite => "file_write",
+            Self::Desktop => "desktop",
+            Self::Process => "process",
+            Self::ReadOnly => "read_only",
+        }
+    }
+
+    fn limits(self) -> CategoryLimits {
+        match self {
+            Self::Shell => CategoryLimits { capacity: 5.0, refill_per_sec: 0.5, max_concurrent: 2 },
+            Self::Process => CategoryLimits { capacity: 5.0, refill_per_sec: 0.5, max_concurrent: 2 },
+            Self::FileWrite => CategoryLimits { capacity: 10.0, refill_per_sec: 2.0, max_concurrent: 4 },
+            Self::Desktop => CategoryLimits { capacity: 10.0, refill_per_sec: 2.0, max_concurrent: 4 },
+            Self::ReadOnly => CategoryLimits { capacity: 30.0, refill_per_sec: 10.0, max_concurrent: 8 },
+        }
+    }
+}
+
+struct CategoryLimits {
+    capacity: f64,
+    refill_per_sec: f64,
+    max_concurrent: usize,
+}
+
+/// Classic token bucket: `capacity` tokens, refilled at `refill_per_sec`, one token per request.
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limits: &CategoryLimits) -> Self {
+        Self {
+            capacity: limits.capacity,
+            refill_per_sec: limits.refill_per_sec,
+            tokens: limits.capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refill based on elapsed time, then try to take one token. `Ok(())` on success; `Err(delay)`
+    /// with how long until a token would be available otherwise.
+    fn try_take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - self.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        }
+    }
+}
+
+struct Governor {
+    bucket: Mutex<TokenBucket>,
+    concurrency: Semaphore,
+    max_concurrent: usize,
+    capacity: f64,
+}
+
+static GOVERNORS: OnceLock<HashMap<&'static str, Governor>> = OnceLock::new();
+
+fn governors() -> &'static HashMap<&'static str, Governor> {
+    GOVERNORS.get_or_init(|| {
+        [
+            ActionCategory::Shell,
+            ActionCategory::FileWrite,
+            ActionCategory::Desktop,
+            ActionCategory::Process,
+            ActionCategory::ReadOnly,
+        ]
+        .into_iter()
+        .map(|category| {
+            let limits = category.limits();
+            let governor = Governor {
+                bucket: Mutex::new(TokenBucket::new(&limits)),
+                concurrency: Semaphore::new(limits.max_concurrent),
+                max_concurrent: limits.max_concurrent,
+                capacity: limits.capacity,
+            };
+            (category.label(), governor)
+        })
+        .collect()
+    })
+}
+
+/// A held concurrency slot for one in-flight action. Drop it when the action finishes so the
+/// next one in its category can run.
+pub struct AdmissionGuard(#[allow(dead_code)] SemaphorePermit<'static>);
+
+/// Try to admit one action of `category`. On success returns a guard that must be held for the
+/// duration of the action; on rejection returns how long (ms) the caller should wait before
+/// retrying.
+pub fn try_admit(category: ActionCategory) -> Result<AdmissionGuard, u64> {
+    let governor = governors().get(category.label()).expect("all categories registered");
+
+    // Reserve a concurrency slot first so a saturated semaphore never costs a token.
+    let permit = governor
+        .concurrency
+        .try_acquire()
+        .map_err(|_| 1000)?;
+
+    let mut bucket = governor.bucket.lock().unwrap();
+    match bucket.try_take() {
+        Ok(()) => {
+            drop(bucket);
+            Ok(AdmissionGuard(permit))
+        }
+        Err(retry_after) => Err(retry_after.as_millis().max(1) as u64),
+    }
+}
+
+/// Current throttle state per category, for `get_status` to surface to the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryStatus {
+    pub category: String,
+    pub tokens_available: f64,
+    pub capacity: f64,
+    pub max_concurrent: usize,
+    pub in_flight: usize,
+}
+
+pub fn snapshot() -> Vec<CategoryStatus> {
+    let mut statuses: Vec<CategoryStatus> = governors()
+        .iter()
+        .map(|(label, governor)| {
+            let tokens_available = governor.bucket.lock().unwrap().tokens;
+            CategoryStatus {
+                category: label.to_string(),
+                tokens_available,
+                capacity: governor.capacity,
+                max_concurrent: governor.max_concurrent,
+                in_flight: governor.max_concurrent - governor.concurrency.available_permits(),
+            }
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.category.cmp(&b.category));
+    statuses
+}
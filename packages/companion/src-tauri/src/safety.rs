@@ -32,6 +32,11 @@ pub struct SafetyVerdict {
     pub risk: RiskLevel,
     pub reason: String,
     pub requires_confirmation: bool,
+    /// True if this operation targets a protected system path or starts with a privileged shell
+    /// verb, which normally needs a higher integrity level (e.g. admin/UAC), so the LLM is told up
+    /// front instead of discovering it through a cryptic access-denied failure. Set from the
+    /// target alone; the Companion's current integrity level is not consulted.
+    pub requires_elevation: bool,
 }
 
 /// Directories that are ALWAYS protected (hard block)
@@ -113,8 +118,106 @@ const PROTECTED_REGISTRY: &[&str] = &[
     "HKLM\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Winlogon",
     "HKLM\\SOFTWARE\\Policies",
     "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
+    // Rule 4 (never disable Defender) extends to the registry, not just `net stop windefend` —
+    // "...\Policies" above already covers "...\Policies\Microsoft\Windows Defender", but the
+    // tamper-protection keys under Windows Defender\Features live outside Policies entirely.
+    "HKLM\\SOFTWARE\\Microsoft\\Windows Defender\\Features",
 ];
 
+/// `Classes\<progid>\shell\open\command` ProgIDs under HKCU that classic UAC-bypass hijacks
+/// target — auto-elevating trusted binaries (fodhelper.exe, computerdefault.exe, eventvwr.exe,
+/// sdclt.exe) consult these per-user keys before their HKLM equivalents, so writing a shell
+/// command into one here runs it elevated from an unprivileged process with no consent prompt.
+/// Distinct from `PROTECTED_REGISTRY` above, which only covers a handful of HKLM boot/security
+/// keys and a couple of Run keys and doesn't reach this per-user hijack pattern at all.
+const UAC_BYPASS_HIJACK_PROGIDS: &[&str] = &["ms-settings", "mscfile", "folder", "exefile"];
+
+/// Trusted Windows binaries known to silently auto-elevate (no UAC consent prompt) when launched
+/// in specific contexts — used by `check_shell_command`'s LOLBIN launch-chain detection.
+const AUTO_ELEVATING_LOLBINS: &[&str] = &[
+    "eventvwr.exe",
+    "fodhelper.exe",
+    "computerdefaults.exe",
+    "computerdefault.exe",
+    "sdclt.exe",
+    "slui.exe",
+    "gpedit.msc",
+];
+
+/// Environment variable prefixes that configure .NET CLR profiler injection — setting any of
+/// these causes the next .NET process (including auto-elevating CLR hosts like gpedit.msc) to
+/// load an attacker DLL as a profiling thread at that process's privilege level.
+const CLR_PROFILER_ENV_PREFIXES: &[&str] = &[
+    "cor_profiler",
+    "cor_enable_profiling",
+    "complus_profiler",
+    "complus_enable_profiling",
+    "coreclr_profiler",
+    "coreclr_enable_profiling",
+];
+
+/// Value names that directly disable a Defender protection (these two are sometimes set outside
+/// the paths `PROTECTED_REGISTRY` lists). No check in this module reads this list, so writing
+/// one of these values is not blocked by its name.
+const DEFENDER_DISABLE_VALUES: &[&str] = &["disableantispyware", "disablerealtimemonitoring"];
+
+/// Known Attack Surface Reduction rule GUIDs, each with a readable name. No check in this module
+/// reads this table, so no verdict names an ASR rule.
+const ASR_RULE_NAMES: &[(&str, &str)] = &[
+    ("d4f940ab-401b-4efc-aadc-ad5f3c50688a", "block Office applications from creating child processes"),
+    ("9e6c4e1f-7d60-472f-ba1a-a39ef669e4b2", "block credential stealing from LSASS"),
+    ("c1db55ab-c21a-4637-bb3f-a12568109d35", "use advanced ransomware protection"),
+    ("75668c1f-73b5-4091-8e2d-2edaa5ed9e14", "block Office applications from creating executable content"),
+    ("01443614-cd74-433a-b99e-2ecdc07bfc25", "block executable files from running unless they meet a prevalence/age/trusted list criterion"),
+];
+
+/// Tools/techniques capable of producing a full process memory dump — the first half of the
+/// credential-dumping triad (dump-capable tool + lsass target + full/ma dump flag) checked by
+/// `command_targets_lsass_dump`.
+const DUMP_CAPABLE_TOOLS: &[&str] = &[
+    "comsvcs.dll",
+    "procdump",
+    "procdump64",
+    "out-minidump",
+    "minidumpwritedump",
+    "nanodump",
+    "dumpert",
+];
+
+/// Filename fragments that mark a destination as an LSASS memory dump regardless of which tool
+/// wrote it.
+const LSASS_DUMP_FILENAME_PATTERNS: &[&str] = &["lsass.dmp", "lsass_", "lsass-"];
+
+/// Filenames of known local-privesc enumeration tools — flagged High (requires confirmation),
+/// not Blocked, since running them reveals a misconfiguration but isn't itself destructive.
+const HACKTOOL_ENUM_FILENAMES: &[&str] = &[
+    "winpeas.bat",
+    "winpeas.exe",
+    "winpeasx64.exe",
+    "winpeasx86.exe",
+    "winpeasany.exe",
+    "linpeas.sh",
+    "windows-privesc-check.py",
+    "windows-privesc-check2.exe",
+];
+
+/// Content markers for the enumeration tools above, checked when file content is available —
+/// catches a renamed copy that still contains the tool's banner/source.
+const HACKTOOL_ENUM_CONTENT_MARKERS: &[&str] = &[
+    "winpeas",
+    "linpeas",
+    "windows-privesc-check",
+];
+
+/// Filenames/strings for credential-theft tools — Blocked, not just flagged, since there's no
+/// legitimate reason for the Companion to stage these.
+const HACKTOOL_CREDTHEFT_FILENAMES: &[&str] = &["wce.exe", "mimikatz.exe", "mimikatz.ps1"];
+const HACKTOOL_CREDTHEFT_CONTENT_MARKERS: &[&str] = &["sekurlsa", "mimikatz"];
+
+/// PowerShell AMSI-bypass snippet markers — patching `amsi.dll`/`AmsiScanBuffer` in memory or
+/// forcing `amsiInitFailed` to make AMSI stop scanning subsequent commands in the same session.
+const AMSI_BYPASS_CONTENT_MARKERS: &[&str] = &["amsiscanbuffer", "amsiinitfailed", "amsi.dll"];
+
 /// Processes that can NEVER be killed
 const PROTECTED_PROCESSES: &[&str] = &[
     "csrss.exe", "lsass.exe", "smss.exe", "wininit.exe",
@@ -198,6 +301,102 @@ pub fn is_protected_registry(path: &str) -> bool {
         .any(|p| path_lower.starts_with(&p.to_lowercase()))
 }
 
+/// Does `normalized` (already lowercased, backslash-normalized) match
+/// `hkcu\software\classes\<progid>\shell\open\command` for one of the known hijack ProgIDs?
+fn is_uac_bypass_hijack_key(normalized: &str) -> bool {
+    UAC_BYPASS_HIJACK_PROGIDS.iter().any(|progid| {
+        let suffix = format!("hkcu\\software\\classes\\{}\\shell\\open\\command", progid);
+        normalized == suffix || normalized.starts_with(&format!("{}\\", suffix))
+    })
+}
+
+/// Does `cmd_lower` launch one of the known auto-elevating LOLBINs? Returns the matched name for
+/// the verdict's reason string.
+fn is_auto_elevating_lolbin(cmd_lower: &str) -> Option<&'static str> {
+    AUTO_ELEVATING_LOLBINS
+        .iter()
+        .find(|&&bin| cmd_lower.contains(bin))
+        .copied()
+}
+
+/// Does `cmd_lower` look like it's also writing a UAC-bypass hijack key — a `reg add`/PowerShell
+/// item-property write targeting one of the known hijack ProgIDs' `shell\open\command` key, or
+/// setting a `DelegateExecute` value under
+/// `HKCU\Software\Classes`? Used to escalate an auto-elevating LOLBIN launch from High to Blocked
+/// when both halves of the hijack-then-invoke pattern appear in the same command line.
+fn command_targets_uac_bypass_hijack(cmd_lower: &str) -> bool {
+    let looks_like_registry_write = cmd_lower.contains("reg add")
+        || cmd_lower.contains("reg.exe add")
+        || cmd_lower.contains("new-itemproperty")
+        || cmd_lower.contains("set-itemproperty");
+    if !looks_like_registry_write {
+        return false;
+    }
+
+    let targets_known_progid = UAC_BYPASS_HIJACK_PROGIDS.iter().any(|progid| {
+        let suffix = format!("classes\\{}\\shell\\open\\command", progid);
+        let suffix_fwd = format!("classes/{}/shell/open/command", progid);
+        cmd_lower.contains(&suffix) || cmd_lower.contains(&suffix_fwd)
+    });
+
+    let sets_delegate_execute = cmd_lower.contains("delegateexecute") && cmd_lower.contains("hkcu");
+
+    targets_known_progid || sets_delegate_execute
+}
+
+/// Does `var_lower` (already lowercased) configure CLR profiler injection?
+fn is_clr_profiler_env_var(var_lower: &str) -> bool {
+    CLR_PROFILER_ENV_PREFIXES.iter().any(|p| var_lower.starts_with(p))
+}
+
+/// Pull `(name, value)` pairs out of cmd.exe `set`/`setx` and PowerShell `$env:` assignments
+/// anywhere in `command` — including inline on a chained command line like
+/// `cmd /c "set COR_PROFILER=... && gpedit.msc"` — so `check_shell_command` can catch CLR
+/// profiler injection even when it's only one clause of a larger command.
+fn extract_env_assignments(command: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+
+    if let Ok(re) = Regex::new(r#"(?i)\bsetx?\s+([A-Za-z_][A-Za-z0-9_]*)\s*[=\s]\s*"?([^"&|\r\n]*)"?"#) {
+        for cap in re.captures_iter(command) {
+            out.push((cap[1].to_string(), cap[2].trim().to_string()));
+        }
+    }
+
+    if let Ok(re) = Regex::new(r#"(?i)\$env:([A-Za-z_][A-Za-z0-9_]*)\s*=\s*"?([^"&|\r\n]*)"?"#) {
+        for cap in re.captures_iter(command) {
+            out.push((cap[1].to_string(), cap[2].trim().to_string()));
+        }
+    }
+
+    out
+}
+
+/// Check an environment-variable assignment (from `set`/`setx`/`$env:`, wherever in the command
+/// line it appears) for the CLR-profiler DLL-injection pattern — `COR_PROFILER`/
+/// `COR_ENABLE_PROFILING`/`COR_PROFILER_PATH` and their `COMPLUS_`/`CORECLR_` equivalents.
+pub fn check_environment_mutation(var: &str, value: &str) -> SafetyVerdict {
+    if is_clr_profiler_env_var(&var.to_lowercase()) {
+        return SafetyVerdict {
+            allowed: false,
+            risk: RiskLevel::Blocked,
+            reason: format!(
+                "BLOCKED: '{}' configures .NET CLR profiler injection — setting it to '{}' would make the next .NET process (including auto-elevating CLR hosts) load an attacker DLL at that process's privilege level",
+                var, value
+            ),
+            requires_confirmation: false,
+            requires_elevation: false,
+        };
+    }
+
+    SafetyVerdict {
+        allowed: true,
+        risk: RiskLevel::Low,
+        reason: format!("Environment variable '{}' set", var),
+        requires_confirmation: false,
+        requires_elevation: false,
+    }
+}
+
 /// Get the user's home directory for sandboxing
 pub fn get_user_home() -> PathBuf {
     dirs::home_dir().unwrap_or_else(|| PathBuf::from("C:\\Users\\Default"))
@@ -220,8 +419,319 @@ pub fn is_user_directory(path: &str) -> bool {
             && !is_protected_path(&normalized))
 }
 
-/// Main safety check for file operations
-pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
+/// Verbs that normally require an elevated (admin) shell to succeed
+const PRIVILEGED_VERBS: &[&str] = &[
+    "runas ", "net ", "netsh ", "sc ", "reg add", "reg delete",
+    "bcdedit", "diskpart", "takeown ", "icacls ",
+];
+
+/// Whether a path lives under a location that's only writable by an elevated process
+/// (e.g. `C:\Windows`, `C:\Program Files`) regardless of whether it's also protected
+fn path_requires_elevation(path: &str) -> bool {
+    let normalized = path.replace('/', "\\").to_lowercase();
+    normalized.starts_with("c:\\windows")
+        || normalized.starts_with("c:\\program files")
+        || normalized.starts_with("c:\\programdata")
+}
+
+/// Whether a shell command starts with a verb that normally needs an elevated shell
+fn command_requires_elevation(cmd_lower: &str) -> bool {
+    PRIVILEGED_VERBS.iter().any(|verb| cmd_lower.starts_with(verb))
+}
+
+/// Whether a command line targets lsass, either by name or (after a `tasklist`/`findstr lsass`
+/// lookup) by PID — the second element of the credential-dumping triad.
+fn command_targets_lsass(cmd_lower: &str) -> bool {
+    cmd_lower.contains("lsass")
+}
+
+/// Whether a command line requests a *full* process dump rather than a partial/mini one — the
+/// third element of the triad. `comsvcs.dll`'s `MiniDump` export takes this as its third arg
+/// (`rundll32 C:\Windows\System32\comsvcs.dll, MiniDump <pid> out.dmp full`); `procdump` spells
+/// it `-ma`.
+fn command_requests_full_dump(cmd_lower: &str) -> bool {
+    cmd_lower.contains("full") || cmd_lower.contains("-ma") || cmd_lower.contains("/ma")
+}
+
+/// Detect the credential-dumping triad on one command line: a dump-capable tool, lsass (by name
+/// or PID via a preceding `tasklist`/`findstr lsass`), and a full/ma dump flag. Any two without
+/// the third is not enough on its own — e.g. `procdump -ma notepad.exe` is unrelated, and
+/// `tasklist | findstr lsass` alone is just process enumeration — but all three together is the
+/// textbook LSASS memory dump used to harvest credentials offline with mimikatz.
+fn command_targets_lsass_dump(cmd_lower: &str) -> bool {
+    let has_dump_tool = DUMP_CAPABLE_TOOLS.iter().any(|t| cmd_lower.contains(t));
+    has_dump_tool && command_targets_lsass(cmd_lower) && command_requests_full_dump(cmd_lower)
+}
+
+/// Whether a write destination filename looks like an LSASS memory dump, regardless of which
+/// tool produced it — catches the dump step even when the command line that wrote it isn't
+/// visible to us (e.g. a dump performed through a GUI tool or an already-running process).
+pub fn is_lsass_dump_filename(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    let filename = lower.rsplit(['/', '\\']).next().unwrap_or(&lower);
+    LSASS_DUMP_FILENAME_PATTERNS.iter().any(|p| filename.contains(p)) && filename.ends_with(".dmp")
+}
+
+/// Check an attempt to open a handle to a named process with a given access mask (or, for
+/// callers without raw access-mask data, a textual description such as "dump" or "read"). The
+/// real threat `check_process_kill` doesn't cover: reading lsass's memory needs no termination
+/// right at all, just `PROCESS_VM_READ`/`PROCESS_QUERY_INFORMATION`, which is exactly what
+/// credential-dumping tools request.
+pub fn check_process_access(process_name: &str, access: &str) -> SafetyVerdict {
+    let name_lower = process_name.to_lowercase();
+    let access_lower = access.to_lowercase();
+
+    if name_lower.contains("lsass")
+        && (access_lower.contains("dump")
+            || access_lower.contains("vm_read")
+            || access_lower.contains("query_information")
+            || access_lower.contains("all_access"))
+    {
+        return SafetyVerdict {
+            allowed: false,
+            risk: RiskLevel::Blocked,
+            reason: format!(
+                "BLOCKED: opening '{}' with {} access is the handle credential-dumping tools request to read its memory — never allowed regardless of intent",
+                process_name, access
+            ),
+            requires_confirmation: false,
+            requires_elevation: false,
+        };
+    }
+
+    SafetyVerdict {
+        allowed: true,
+        risk: RiskLevel::Medium,
+        reason: format!("Process access to '{}' ({}) outside known credential-theft patterns", process_name, access),
+        requires_confirmation: true,
+        requires_elevation: false,
+    }
+}
+
+/// Signature-based detector for offensive-security tooling being written/created by the agent —
+/// a lightweight local "is the model about to drop a hacktool" gate, checked by filename always
+/// and by content signature when the caller has bytes to inspect (e.g. before a file write).
+/// Enumeration tooling (winPEAS, linPEAS, windows-privesc-check) is High — running it reveals a
+/// misconfiguration but isn't destructive on its own. Credential-theft tools (wce, mimikatz) and
+/// AMSI-bypass snippets are Blocked outright.
+pub fn scan_payload(name: &str, content: Option<&[u8]>) -> SafetyVerdict {
+    let name_lower = name.to_lowercase();
+    let content_lower = content.map(|c| String::from_utf8_lossy(c).to_lowercase());
+
+    if HACKTOOL_CREDTHEFT_FILENAMES.iter().any(|f| name_lower.ends_with(f)) {
+        return SafetyVerdict {
+            allowed: false,
+            risk: RiskLevel::Blocked,
+            reason: format!("BLOCKED: '{}' matches a known credential-theft tool filename", name),
+            requires_confirmation: false,
+            requires_elevation: false,
+        };
+    }
+
+    if let Some(text) = &content_lower {
+        if let Some(marker) = HACKTOOL_CREDTHEFT_CONTENT_MARKERS.iter().find(|m| text.contains(**m)) {
+            return SafetyVerdict {
+                allowed: false,
+                risk: RiskLevel::Blocked,
+                reason: format!("BLOCKED: '{}' contains the credential-theft signature '{}'", name, marker),
+                requires_confirmation: false,
+                requires_elevation: false,
+            };
+        }
+
+        if let Some(marker) = AMSI_BYPASS_CONTENT_MARKERS.iter().find(|m| text.contains(**m)) {
+            return SafetyVerdict {
+                allowed: false,
+                risk: RiskLevel::Blocked,
+                reason: format!("BLOCKED: '{}' contains the AMSI-bypass signature '{}'", name, marker),
+                requires_confirmation: false,
+                requires_elevation: false,
+            };
+        }
+    }
+
+    if HACKTOOL_ENUM_FILENAMES.iter().any(|f| name_lower.ends_with(f)) {
+        return SafetyVerdict {
+            allowed: true,
+            risk: RiskLevel::High,
+            reason: format!("'{}' matches a known privilege-escalation enumeration tool filename — requires confirmation", name),
+            requires_confirmation: true,
+            requires_elevation: false,
+        };
+    }
+
+    if let Some(text) = &content_lower {
+        if let Some(marker) = HACKTOOL_ENUM_CONTENT_MARKERS.iter().find(|m| text.contains(**m)) {
+            return SafetyVerdict {
+                allowed: true,
+                risk: RiskLevel::High,
+                reason: format!("'{}' contains the enumeration-tool signature '{}' — requires confirmation", name, marker),
+                requires_confirmation: true,
+                requires_elevation: false,
+            };
+        }
+    }
+
+    SafetyVerdict {
+        allowed: true,
+        risk: RiskLevel::Safe,
+        reason: "No known hacktool signature matched".into(),
+        requires_confirmation: false,
+        requires_elevation: false,
+    }
+}
+
+/// The Windows Mandatory Integrity Control (MIC) label of the Companion process's token
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum IntegrityLevel {
+    Untrusted,
+    Low,
+    Medium,
+    High,
+    System,
+    Unknown,
+}
+
+impl IntegrityLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntegrityLevel::Untrusted => "Untrusted",
+            IntegrityLevel::Low => "Low",
+            IntegrityLevel::Medium => "Medium",
+            IntegrityLevel::High => "High (elevated)",
+            IntegrityLevel::System => "System",
+            IntegrityLevel::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Query the current process token's mandatory integrity level. Always `Unknown` off Windows.
+pub fn current_integrity_level() -> IntegrityLevel {
+    #[cfg(windows)]
+    {
+        win_integrity::query()
+            .map(|(rid, _restricted)| win_integrity::rid_to_level(rid))
+            .unwrap_or(IntegrityLevel::Unknown)
+    }
+    #[cfg(not(windows))]
+    {
+        IntegrityLevel::Unknown
+    }
+}
+
+/// Whether the current process token is a restricted token (e.g. running inside a sandbox
+/// or AppContainer). Always `false` off Windows.
+pub fn is_token_restricted() -> bool {
+    #[cfg(windows)]
+    {
+        win_integrity::query().map(|(_rid, restricted)| restricted).unwrap_or(false)
+    }
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Raw `GetTokenInformation`/`TokenIntegrityLevel` FFI, without pulling in a winapi crate.
+#[cfg(windows)]
+mod win_integrity {
+    use super::IntegrityLevel;
+    use std::ffi::c_void;
+
+    const TOKEN_QUERY: u32 = 0x0008;
+    const TOKEN_INTEGRITY_LEVEL: u32 = 25;
+
+    #[repr(C)]
+    struct SidAndAttributes {
+        sid: *mut c_void,
+        attributes: u32,
+    }
+
+    #[repr(C)]
+    struct TokenMandatoryLabel {
+        label: SidAndAttributes,
+    }
+
+    extern "system" {
+        fn GetCurrentProcess() -> *mut c_void;
+        fn OpenProcessToken(process: *mut c_void, desired_access: u32, token_handle: *mut *mut c_void) -> i32;
+        fn GetTokenInformation(
+            token_handle: *mut c_void,
+            token_information_class: u32,
+            token_information: *mut c_void,
+            token_information_length: u32,
+            return_length: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+        fn IsTokenRestricted(token_handle: *mut c_void) -> i32;
+        fn GetSidSubAuthorityCount(sid: *mut c_void) -> *mut u8;
+        fn GetSidSubAuthority(sid: *mut c_void, sub_authority: u32) -> *mut u32;
+    }
+
+    /// Returns (integrity RID, is_restricted) for the current process's token.
+    pub fn query() -> Result<(u32, bool), String> {
+        unsafe {
+            let mut token: *mut c_void = std::ptr::null_mut();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+                return Err("OpenProcessToken failed".into());
+            }
+
+            // First call probes the required buffer size.
+            let mut needed: u32 = 0;
+            GetTokenInformation(token, TOKEN_INTEGRITY_LEVEL, std::ptr::null_mut(), 0, &mut needed);
+            if needed == 0 {
+                CloseHandle(token);
+                return Err("GetTokenInformation size probe failed".into());
+            }
+
+            let mut buf: Vec<u8> = vec![0u8; needed as usize];
+            let ok = GetTokenInformation(
+                token,
+                TOKEN_INTEGRITY_LEVEL,
+                buf.as_mut_ptr() as *mut c_void,
+                needed,
+                &mut needed,
+            );
+            if ok == 0 {
+                CloseHandle(token);
+                return Err("GetTokenInformation failed".into());
+            }
+
+            let label = &*(buf.as_ptr() as *const TokenMandatoryLabel);
+            let sid = label.label.sid;
+            let count = *GetSidSubAuthorityCount(sid);
+            let rid = if count > 0 {
+                *GetSidSubAuthority(sid, (count - 1) as u32)
+            } else {
+                0
+            };
+            let restricted = IsTokenRestricted(token) != 0;
+
+            CloseHandle(token);
+            Ok((rid, restricted))
+        }
+    }
+
+    /// Map the top sub-authority (RID) of the integrity label SID to a level.
+    /// Untrusted = 0x0000, Low = 0x1000, Medium(+) = 0x2000-0x2fff, High = 0x3000-0x3fff,
+    /// System+ = 0x4000 and above.
+    pub fn rid_to_level(rid: u32) -> IntegrityLevel {
+        match rid {
+            0x0000 => IntegrityLevel::Untrusted,
+            0x1000..=0x1fff => IntegrityLevel::Low,
+            0x2000..=0x2fff => IntegrityLevel::Medium,
+            0x3000..=0x3fff => IntegrityLevel::High,
+            r if r >= 0x4000 => IntegrityLevel::System,
+            _ => IntegrityLevel::Unknown,
+        }
+    }
+}
+
+/// Main safety check for file operations. `content` is the bytes being written, when the caller
+/// has them available (e.g. a `write` action) — passed through to `scan_payload` so a known
+/// hacktool signature blocks the write even under an innocuous filename. Pass `None` when no
+/// content applies (reads, deletes, directory creation, moves without new bytes).
+pub fn check_file_operation(operation: &str, path: &str, content: Option<&[u8]>) -> SafetyVerdict {
     let op = operation.to_lowercase();
 
     // Read operations are always safe
@@ -231,6 +741,7 @@ pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
             risk: RiskLevel::Safe,
             reason: "Read operations are always allowed".into(),
             requires_confirmation: false,
+            requires_elevation: false,
         };
     }
 
@@ -241,6 +752,7 @@ pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
             risk: RiskLevel::Blocked,
             reason: format!("BLOCKED: '{}' is a system-protected path. This operation is never allowed.", path),
             requires_confirmation: false,
+            requires_elevation: path_requires_elevation(path),
         };
     }
 
@@ -252,17 +764,34 @@ pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
             risk: RiskLevel::Medium,
             reason: format!("Delete operation on '{}' — path is not system-protected", path),
             requires_confirmation: false,
+            requires_elevation: false,
         };
     }
 
     // Write/create/move operations
     if op == "write" || op == "create" || op == "move" || op == "copy" || op == "rename" {
+        if is_lsass_dump_filename(path) {
+            return SafetyVerdict {
+                allowed: false,
+                risk: RiskLevel::Blocked,
+                reason: format!("BLOCKED: '{}' looks like an LSASS memory dump — writing credential-dump output is never allowed", path),
+                requires_confirmation: false,
+                requires_elevation: false,
+            };
+        }
+
+        let payload_verdict = scan_payload(path, content);
+        if !payload_verdict.allowed || payload_verdict.risk == RiskLevel::High {
+            return payload_verdict;
+        }
+
         if !is_user_directory(path) {
             return SafetyVerdict {
                 allowed: false,
                 risk: RiskLevel::Blocked,
                 reason: format!("BLOCKED: Cannot write to '{}' — outside user directory", path),
                 requires_confirmation: false,
+                requires_elevation: path_requires_elevation(path),
             };
         }
 
@@ -271,6 +800,7 @@ pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
             risk: RiskLevel::Medium,
             reason: "File operation within user directory".into(),
             requires_confirmation: false,
+            requires_elevation: false,
         };
     }
 
@@ -280,6 +810,7 @@ pub fn check_file_operation(operation: &str, path: &str) -> SafetyVerdict {
         risk: RiskLevel::Medium,
         reason: format!("Unknown operation '{}' — requires confirmation", operation),
         requires_confirmation: true,
+        requires_elevation: false,
     }
 }
 
@@ -292,11 +823,63 @@ pub fn check_shell_command(command: &str) -> SafetyVerdict {
             risk: RiskLevel::Blocked,
             reason,
             requires_confirmation: false,
+            requires_elevation: false,
         };
     }
 
     let cmd_lower = command.to_lowercase();
 
+    // Credential-dumping triad — dump-capable tool + lsass target + full/ma dump flag — checked
+    // right after the hard-blocked command list since this is the highest-value local attack the safety charter names
+    // but (before this check) does not technically enforce.
+    if command_targets_lsass_dump(&cmd_lower) {
+        return SafetyVerdict {
+            allowed: false,
+            risk: RiskLevel::Blocked,
+            reason: "BLOCKED: command line matches the LSASS credential-dumping pattern (dump-capable tool + lsass target + full/ma dump flag) — never allowed".into(),
+            requires_confirmation: false,
+            requires_elevation: false,
+        };
+    }
+
+    // CLR profiler DLL-injection env vars set inline on the same command line (cmd.exe
+    // `set`/`setx` or PowerShell `$env:`) — checked before anything else below since this is the
+    // most specific and most dangerous signal, and the same command line may also happen to
+    // match a LOLBIN launch or a generic medium/high-risk command.
+    for (var, value) in extract_env_assignments(command) {
+        let verdict = check_environment_mutation(&var, &value);
+        if verdict.risk == RiskLevel::Blocked {
+            return verdict;
+        }
+    }
+
+    // Trusted binaries that silently auto-elevate — the payload trigger in the classic two-step
+    // "hijack a per-user class key, then invoke the auto-elevating binary" UAC bypass. Flagged
+    // High on their own; escalated to Blocked below if the same command line also looks like it's
+    // writing the hijack key (see `command_targets_uac_bypass_hijack`), since neither half looks
+    // dangerous alone but the combination is the actual exploit.
+    if let Some(bin) = is_auto_elevating_lolbin(&cmd_lower) {
+        if command_targets_uac_bypass_hijack(&cmd_lower) {
+            return SafetyVerdict {
+                allowed: false,
+                risk: RiskLevel::Blocked,
+                reason: format!(
+                    "BLOCKED: command writes a UAC-bypass hijack key and launches auto-elevating '{}' in the same command line — classic hijack-then-invoke escalation",
+                    bin
+                ),
+                requires_confirmation: false,
+                requires_elevation: false,
+            };
+        }
+        return SafetyVerdict {
+            allowed: true,
+            risk: RiskLevel::High,
+            reason: format!("'{}' is a known auto-elevating binary — requires user confirmation", bin),
+            requires_confirmation: true,
+            requires_elevation: false,
+        };
+    }
+
     // Safe read-only commands
     let safe_commands = [
         "dir ", "ls ", "type ", "cat ", "echo ", "where ", "whoami",
@@ -311,6 +894,7 @@ pub fn check_shell_command(command: &str) -> SafetyVerdict {
                 risk: RiskLevel::Safe,
                 reason: "Read-only command".into(),
                 requires_confirmation: false,
+                requires_elevation: false,
             };
         }
     }
@@ -329,6 +913,7 @@ pub fn check_shell_command(command: &str) -> SafetyVerdict {
                 risk: RiskLevel::Medium,
                 reason: format!("Application/file command: {}", med.trim()),
                 requires_confirmation: false,
+                requires_elevation: false,
             };
         }
     }
@@ -347,6 +932,7 @@ pub fn check_shell_command(command: &str) -> SafetyVerdict {
                 risk: RiskLevel::High,
                 reason: format!("High-risk command '{}' requires user confirmation", high.trim()),
                 requires_confirmation: true,
+                requires_elevation: command_requires_elevation(&cmd_lower),
             };
         }
     }
@@ -357,6 +943,7 @@ pub fn check_shell_command(command: &str) -> SafetyVerdict {
         risk: RiskLevel::High,
         reason: "Unknown command — requires user confirmation".into(),
         requires_confirmation: true,
+        requires_elevation: false,
     }
 }
 
@@ -368,6 +955,7 @@ pub fn check_process_kill(process_name: &str) -> SafetyVerdict {
             risk: RiskLevel::Blocked,
             reason: format!("BLOCKED: '{}' is a critical system process and cannot be terminated", process_name),
             requires_confirmation: false,
+            requires_elevation: false,
         };
     }
 
@@ -376,6 +964,7 @@ pub fn check_process_kill(process_name: &str) -> SafetyVerdict {
         risk: RiskLevel::High,
         reason: format!("Killing process '{}' requires confirmation", process_name),
         requires_confirmation: true,
+        requires_elevation: false,
     }
 }
 
@@ -455,19 +1044,60 @@ mod tests {
 
     #[test]
     fn test_file_operations() {
-        let read = check_file_operation("read", "C:\\Windows\\System32\\config");
+        let read = check_file_operation("read", "C:\\Windows\\System32\\config", None);
         assert!(read.allowed);
         assert_eq!(read.risk, RiskLevel::Safe);
 
-        let delete_sys = check_file_operation("delete", "C:\\Windows\\System32\\cmd.exe");
+        let delete_sys = check_file_operation("delete", "C:\\Windows\\System32\\cmd.exe", None);
         assert!(!delete_sys.allowed);
         assert_eq!(delete_sys.risk, RiskLevel::Blocked);
 
-        let write_sys = check_file_operation("write", "C:\\Windows\\test.txt");
+        let write_sys = check_file_operation("write", "C:\\Windows\\test.txt", None);
         assert!(!write_sys.allowed);
         assert_eq!(write_sys.risk, RiskLevel::Blocked);
     }
 
+    #[test]
+    fn test_lsass_dump_detection() {
+        assert!(command_targets_lsass_dump(
+            "rundll32.exe c:\\windows\\system32\\comsvcs.dll, minidump 612 c:\\temp\\lsass.dmp full"
+        ));
+        assert!(command_targets_lsass_dump("procdump64.exe -ma lsass.exe lsass.dmp"));
+        assert!(!command_targets_lsass_dump("procdump64.exe -ma notepad.exe dump.dmp"));
+        assert!(!command_targets_lsass_dump("tasklist | findstr lsass"));
+
+        assert!(is_lsass_dump_filename("C:\\temp\\lsass.dmp"));
+        assert!(is_lsass_dump_filename("lsass_20260727.dmp"));
+        assert!(!is_lsass_dump_filename("C:\\temp\\notes.dmp"));
+
+        let blocked = check_process_access("lsass.exe", "dump");
+        assert!(!blocked.allowed);
+        assert_eq!(blocked.risk, RiskLevel::Blocked);
+
+        let ok = check_process_access("notepad.exe", "dump");
+        assert!(ok.allowed);
+    }
+
+    #[test]
+    fn test_scan_payload() {
+        let enum_tool = scan_payload("winPEAS.bat", None);
+        assert!(enum_tool.allowed);
+        assert_eq!(enum_tool.risk, RiskLevel::High);
+        assert!(enum_tool.requires_confirmation);
+
+        let credtheft = scan_payload("notes.txt", Some(b"Invoke-Mimikatz -DumpCreds; sekurlsa::logonpasswords"));
+        assert!(!credtheft.allowed);
+        assert_eq!(credtheft.risk, RiskLevel::Blocked);
+
+        let amsi_bypass = scan_payload("patch.ps1", Some(b"[Ref].Assembly.GetType('System.Management.Automation.AmsiUtils').GetField('amsiInitFailed','NonPublic,Static')"));
+        assert!(!amsi_bypass.allowed);
+        assert_eq!(amsi_bypass.risk, RiskLevel::Blocked);
+
+        let benign = scan_payload("readme.md", Some(b"just a normal readme"));
+        assert!(benign.allowed);
+        assert_eq!(benign.risk, RiskLevel::Safe);
+    }
+
     #[test]
     fn test_shell_commands() {
         let safe = check_shell_command("dir C:\\Users");
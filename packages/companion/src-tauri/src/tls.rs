@@ -0,0 +1,94 @@
+//! # Gateway TLS Trust Policy
+//!
+//! The WebSocket loop, pairing, token refresh, and the chat/voice requests in `commands.rs`
+//! share one trust policy: the OS's native root certificates, or, if the paired Gateway has a
+//! pinned certificate fingerprint stored alongside its `CompanionCredentials`, a hard pin that
+//! replaces chain validation entirely. A pin mismatch fails the handshake outright rather than
+//! falling back to the system trust store, so a pinned companion can never be quietly redirected
+//! to a different Gateway identity. The session list/history/delete requests in `commands.rs`,
+//! the artifact fetch, and `voice.rs`'s Gateway requests use reqwest's default client instead,
+//! so a pin does not apply to them.
+
+use crate::connection::CompanionCredentials;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Load the OS's native root certificates into a rustls root store. Certificates the parser
+/// rejects are skipped rather than failing the whole store — real-world OS stores usually carry
+/// a few of these.
+fn native_root_store() -> Result<rustls::RootCertStore, String> {
+    let mut store = rustls::RootCertStore::empty();
+    let certs = rustls_native_certs::load_native_certs()
+        .map_err(|e| format!("Failed to load native root certificates: {}", e))?;
+    for cert in certs {
+        let _ = store.add(&rustls::Certificate(cert.0));
+    }
+    Ok(store)
+}
+
+/// Accepts a server certificate only if its SHA-256 fingerprint matches the pin, skipping
+/// ordinary chain-of-trust validation entirely — used only when the user has pinned a Gateway.
+struct PinnedCertVerifier {
+    pinned_sha256_hex: String,
+}
+
+impl rustls::client::ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        let digest = sha256_hex(&end_entity.0);
+        if digest.eq_ignore_ascii_case(&self.pinned_sha256_hex) {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "Gateway certificate pin mismatch: expected {}, got {}",
+                self.pinned_sha256_hex, digest
+            )))
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::Digest as _;
+    let digest = sha2::Sha256::digest(bytes);
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Build the `rustls::ClientConfig` a Gateway connection should use: native roots, or a hard
+/// certificate pin when `creds` carries one.
+pub fn client_config(creds: Option<&CompanionCredentials>) -> Result<rustls::ClientConfig, String> {
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_defaults();
+
+    let config = match creds.and_then(|c| c.pinned_cert_sha256.as_deref()) {
+        Some(pin) => builder
+            .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier {
+                pinned_sha256_hex: pin.to_string(),
+            }))
+            .with_no_client_auth(),
+        None => builder
+            .with_root_certificates(native_root_store()?)
+            .with_no_client_auth(),
+    };
+
+    Ok(config)
+}
+
+/// `tokio_tungstenite` connector for the Gateway WebSocket, honoring the same trust policy.
+pub fn ws_connector(creds: Option<&CompanionCredentials>) -> Result<tokio_tungstenite::Connector, String> {
+    Ok(tokio_tungstenite::Connector::Rustls(Arc::new(client_config(creds)?)))
+}
+
+/// `reqwest::Client` for Gateway HTTP calls, honoring the same trust policy.
+pub fn http_client(creds: Option<&CompanionCredentials>) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .use_preconfigured_tls(client_config(creds)?)
+        .build()
+        .map_err(|e| format!("HTTP client build error: {}", e))
+}
@@ -3,6 +3,13 @@
 //! Handles microphone capture → WAV encoding → send to Gateway STT,
 //! and receives TTS audio from Gateway → plays back via speakers.
 //! Uses cpal for capture and rodio for playback.
+//! Also supports fully offline STT via a bundled whisper.cpp model (see `transcribe_local`),
+//! used instead of `transcribe` when the caller has no network round-trip to spare.
+//! `speak` similarly has an offline counterpart: the `tts` crate (SAPI on Windows) lets
+//! `TtsBackend::Local`/`LocalFallback` speak without a Gateway round-trip at all, or only as a
+//! fallback when the Gateway call fails — see `speak_local`.
+//! `record` can optionally run captured audio through a spectral noise gate (see
+//! `spectral_denoise`) before WAV encoding, to clean up STT input in noisy rooms.
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::io::Cursor;
@@ -19,12 +26,81 @@ pub struct CapturedAudio {
     pub wav_base64: String,
 }
 
+/// A running full-duplex voice stream; dropping/clearing this stops both its capture and
+/// playback threads.
+struct StreamHandle {
+    active: Arc<AtomicBool>,
+}
+
+/// Number of consecutive voiced frames required before the hangover counter is allowed to end
+/// the recording — without this, a moment of leading noise right at start-of-speech could trip
+/// the hangover logic before the user has said anything.
+const MIN_VOICED_FRAMES_TO_ARM: u32 = 3;
+
+/// Which device(s) `record` captures from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureSource {
+    /// The default input device — the original, and still default, behavior.
+    Microphone,
+    /// The default output device's render stream (WASAPI loopback on Windows), so ForgeAI can
+    /// transcribe the other side of a call or a playing video instead of only the user.
+    SystemAudio,
+    /// Both of the above, summed sample-aligned into one mono stream — for meeting notes where
+    /// both sides of a conversation need to be captured together.
+    Mixed,
+}
+
+impl Default for CaptureSource {
+    fn default() -> Self {
+        CaptureSource::Microphone
+    }
+}
+
+/// Which path `speak` takes to produce audio.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsBackend {
+    /// Always use the Gateway's `/api/voice/synthesize` endpoint; fail if that fails.
+    Gateway,
+    /// Always synthesize locally (SAPI on Windows via the `tts` crate); never touch the network.
+    Local,
+    /// Try the Gateway first; on network error or timeout, fall back to local synthesis instead
+    /// of returning `Err`.
+    LocalFallback,
+}
+
 /// Voice engine for capture and playback
 pub struct VoiceEngine {
     recording: Arc<AtomicBool>,
     max_duration_secs: u32,
-    silence_threshold: f32,
-    silence_timeout_ms: u64,
+    /// Frame size for voice-activity detection, in milliseconds (default 30ms = 480 samples at
+    /// 16kHz).
+    vad_frame_ms: u32,
+    /// How far above the rolling noise floor a frame's sub-band energy must be to count as
+    /// voiced. Higher is more conservative (less sensitive to quiet speech, more resistant to hum).
+    vad_aggressiveness: f32,
+    /// Consecutive unvoiced frames required to end the recording once speech has been detected.
+    vad_hangover_frames: u32,
+    stream: Option<StreamHandle>,
+    whisper: Option<whisper_rs::WhisperContext>,
+    whisper_model_path: Option<String>,
+    tts_backend: TtsBackend,
+    tts_voice: Option<String>,
+    tts_rate: f32,
+    tts_pitch: f32,
+    /// Whether `record` runs captured audio through `spectral_denoise` before WAV encoding.
+    denoise: bool,
+    /// How aggressively to subtract the estimated noise spectrum (higher = more suppression,
+    /// more risk of removing quiet speech).
+    denoise_alpha: f32,
+    /// Floor below which a bin's magnitude is never gated, as a fraction of its original
+    /// magnitude — keeps spectral subtraction from fully zeroing bins (musical noise).
+    denoise_beta: f32,
+    /// Name of the chosen microphone (as returned by `list_input_devices`), or `None` for the
+    /// host default. Looked up by name in `record` on every call since devices can disconnect
+    /// and reconnect between recordings.
+    input_device: Option<String>,
 }
 
 impl VoiceEngine {
@@ -32,16 +108,56 @@ impl VoiceEngine {
         Self {
             recording: Arc::new(AtomicBool::new(false)),
             max_duration_secs: 30,
-            silence_threshold: 0.01,
-            silence_timeout_ms: 1500,
+            vad_frame_ms: 30,
+            vad_aggressiveness: 3.0,
+            vad_hangover_frames: 25,
+            stream: None,
+            whisper: None,
+            whisper_model_path: None,
+            tts_backend: TtsBackend::LocalFallback,
+            tts_voice: None,
+            tts_rate: 1.0,
+            tts_pitch: 1.0,
+            denoise: false,
+            denoise_alpha: 2.0,
+            denoise_beta: 0.02,
+            input_device: load_persisted_input_device(),
         }
     }
 
-    /// Configure voice engine parameters
-    pub fn configure(&mut self, max_duration_secs: u32, silence_threshold: f32, silence_timeout_ms: u64) {
+    /// Configure voice engine parameters, including the frame voice-activity detector used by
+    /// `record` to decide when the user has stopped speaking.
+    pub fn configure(&mut self, max_duration_secs: u32, vad_frame_ms: u32, vad_aggressiveness: f32, vad_hangover_frames: u32) {
         self.max_duration_secs = max_duration_secs;
-        self.silence_threshold = silence_threshold;
-        self.silence_timeout_ms = silence_timeout_ms;
+        self.vad_frame_ms = vad_frame_ms;
+        self.vad_aggressiveness = vad_aggressiveness;
+        self.vad_hangover_frames = vad_hangover_frames;
+    }
+
+    /// Configure `speak`'s backend and, for local synthesis, which voice/rate/pitch to use.
+    /// `voice` is a platform voice name as returned by `list_tts_voices`; `None` uses the
+    /// platform default. `rate`/`pitch` are multipliers around 1.0.
+    pub fn configure_tts(&mut self, backend: TtsBackend, voice: Option<String>, rate: f32, pitch: f32) {
+        self.tts_backend = backend;
+        self.tts_voice = voice;
+        self.tts_rate = rate;
+        self.tts_pitch = pitch;
+    }
+
+    /// Enable/disable the spectral noise gate `record` applies before WAV encoding, and tune its
+    /// subtraction strength (`alpha`) and noise floor (`beta`). See `spectral_denoise`.
+    pub fn configure_denoise(&mut self, enabled: bool, alpha: f32, beta: f32) {
+        self.denoise = enabled;
+        self.denoise_alpha = alpha;
+        self.denoise_beta = beta;
+    }
+
+    /// Choose the microphone `record` captures from by device name (as returned by
+    /// `list_input_devices`), persisting the choice to the Tauri config dir so it survives
+    /// restarts. `None` resets to the host default.
+    pub fn set_input_device(&mut self, name: Option<String>) -> Result<(), String> {
+        self.input_device = name.clone();
+        save_persisted_input_device(name.as_deref())
     }
 
     /// Is currently recording?
@@ -54,9 +170,121 @@ impl VoiceEngine {
         self.recording.store(false, Ordering::Relaxed);
     }
 
-    /// Record audio from microphone until silence or max duration.
+    /// Record audio until silence or max duration, from whichever device(s) `source` selects.
     /// Returns base64-encoded WAV data ready to send to Gateway STT.
-    pub fn record(&self) -> Result<CapturedAudio, String> {
+    pub fn record(&self, source: CaptureSource) -> Result<CapturedAudio, String> {
+        if self.recording.load(Ordering::Relaxed) {
+            return Err("Already recording".into());
+        }
+
+        self.recording.store(true, Ordering::Relaxed);
+        let recording = self.recording.clone();
+        let max_samples = (16000 * self.max_duration_secs) as usize;
+        let frame_samples = (16000 * self.vad_frame_ms / 1000).max(1) as usize;
+        let aggressiveness = self.vad_aggressiveness;
+        let hangover_frames = self.vad_hangover_frames;
+
+        let (streams, rx) = open_capture_streams(source, self.input_device.as_deref())?;
+
+        log::info!("Voice: recording started ({:?})", source);
+
+        let mut all_samples: Vec<f32> = Vec::with_capacity(max_samples);
+        let mut pending: Vec<f32> = Vec::new();
+        let start = std::time::Instant::now();
+
+        // Rolling per-sub-band noise floor, updated only on unvoiced frames (see `FrameVad`).
+        let mut vad = FrameVad::new(aggressiveness);
+        let mut voiced_run: u32 = 0;
+        let mut unvoiced_run: u32 = 0;
+        let mut armed = false; // true once enough voiced frames have been seen to allow end-of-speech
+
+        // Capture loop — stops once speech has been detected and the hangover elapses, on max
+        // duration, or on manual stop.
+        'capture: while recording.load(Ordering::Relaxed) {
+            match rx.recv_timeout(std::time::Duration::from_millis(50)) {
+                Ok(samples) => {
+                    pending.extend_from_slice(&samples);
+                    all_samples.extend_from_slice(&samples);
+
+                    while pending.len() >= frame_samples {
+                        let frame: Vec<i16> = pending
+                            .drain(..frame_samples)
+                            .map(|s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
+                            .collect();
+
+                        if vad.is_voiced(&frame) {
+                            voiced_run += 1;
+                            unvoiced_run = 0;
+                            if voiced_run >= MIN_VOICED_FRAMES_TO_ARM {
+                                armed = true;
+                            }
+                        } else {
+                            voiced_run = 0;
+                            unvoiced_run += 1;
+                        }
+
+                        if armed && unvoiced_run >= hangover_frames {
+                            log::info!("Voice: end of speech detected (VAD hangover)");
+                            break 'capture;
+                        }
+                    }
+
+                    if all_samples.len() >= max_samples {
+                        log::info!("Voice: max duration reached");
+                        break;
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                    if start.elapsed().as_secs() >= self.max_duration_secs as u64 {
+                        break;
+                    }
+                    continue;
+                }
+                Err(_) => break,
+            }
+        }
+
+        drop(streams);
+        recording.store(false, Ordering::Relaxed);
+
+        let duration_ms = (all_samples.len() as f64 / 16.0) as u64;
+        log::info!(
+            "Voice: recorded {} samples ({}ms)",
+            all_samples.len(),
+            duration_ms
+        );
+
+        if all_samples.len() < 1600 {
+            return Err("Recording too short (< 100ms)".into());
+        }
+
+        let sample_count = all_samples.len();
+        let samples_to_encode = if self.denoise {
+            spectral_denoise(&all_samples, 16000, self.denoise_alpha, self.denoise_beta)
+        } else {
+            all_samples
+        };
+
+        // Encode to WAV
+        let wav_data = encode_wav(&samples_to_encode, 16000)?;
+        let wav_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &wav_data);
+
+        Ok(CapturedAudio {
+            duration_ms,
+            sample_rate: 16000,
+            samples: sample_count,
+            wav_base64,
+        })
+    }
+
+    /// Like `record`, but emits live progress so the frontend can render a VU meter instead of
+    /// waiting in silence for the final WAV: a `voice-audio-level` event per captured chunk
+    /// (RMS, peak amplitude, elapsed ms) and `voice-state` events for `started`/`speech-detected`/
+    /// `silence`/`stopped`, alongside the same `listening`/`processing`/etc. values `chat_voice`
+    /// already emits on that event.
+    pub fn record_with_events(&self, app_handle: &tauri::AppHandle) -> Result<CapturedAudio, String> {
+        use tauri::Emitter;
+
         if self.recording.load(Ordering::Relaxed) {
             return Err("Already recording".into());
         }
@@ -64,8 +292,9 @@ impl VoiceEngine {
         self.recording.store(true, Ordering::Relaxed);
         let recording = self.recording.clone();
         let max_samples = (16000 * self.max_duration_secs) as usize;
-        let silence_threshold = self.silence_threshold;
-        let silence_timeout_ms = self.silence_timeout_ms;
+        let frame_samples = (16000 * self.vad_frame_ms / 1000).max(1) as usize;
+        let aggressiveness = self.vad_aggressiveness;
+        let hangover_frames = self.vad_hangover_frames;
 
         let host = cpal::default_host();
         let device = host
@@ -94,37 +323,63 @@ impl VoiceEngine {
         stream.play().map_err(|e| format!("Failed to start recording: {}", e))?;
 
         log::info!("Voice: recording started");
+        let _ = app_handle.emit("voice-state", serde_json::json!({ "state": "started" }));
 
         let mut all_samples: Vec<f32> = Vec::with_capacity(max_samples);
-        let mut last_voice_time = std::time::Instant::now();
+        let mut pending: Vec<f32> = Vec::new();
         let start = std::time::Instant::now();
 
-        // Capture loop — stops on silence, max duration, or manual stop
-        while recording.load(Ordering::Relaxed) {
+        let mut vad = FrameVad::new(aggressiveness);
+        let mut voiced_run: u32 = 0;
+        let mut unvoiced_run: u32 = 0;
+        let mut armed = false;
+
+        'capture: while recording.load(Ordering::Relaxed) {
             match rx.recv_timeout(std::time::Duration::from_millis(50)) {
                 Ok(samples) => {
-                    // Check for voice activity (RMS energy)
-                    let rms: f32 = (samples.iter().map(|s| s * s).sum::<f32>()
-                        / samples.len() as f32)
-                        .sqrt();
-
-                    if rms > silence_threshold {
-                        last_voice_time = std::time::Instant::now();
-                    }
+                    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt();
+                    let peak = samples.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+                    let _ = app_handle.emit(
+                        "voice-audio-level",
+                        serde_json::json!({
+                            "rms": rms.min(1.0),
+                            "peak": peak.min(1.0),
+                            "elapsedMs": start.elapsed().as_millis() as u64,
+                        }),
+                    );
 
+                    pending.extend_from_slice(&samples);
                     all_samples.extend_from_slice(&samples);
 
-                    // Stop conditions
+                    while pending.len() >= frame_samples {
+                        let frame: Vec<i16> = pending
+                            .drain(..frame_samples)
+                            .map(|s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
+                            .collect();
+
+                        if vad.is_voiced(&frame) {
+                            voiced_run += 1;
+                            unvoiced_run = 0;
+                            if voiced_run >= MIN_VOICED_FRAMES_TO_ARM && !armed {
+                                armed = true;
+                                let _ = app_handle.emit("voice-state", serde_json::json!({ "state": "speech-detected" }));
+                            }
+                        } else {
+                            voiced_run = 0;
+                            unvoiced_run += 1;
+                        }
+
+                        if armed && unvoiced_run >= hangover_frames {
+                            log::info!("Voice: end of speech detected (VAD hangover)");
+                            let _ = app_handle.emit("voice-state", serde_json::json!({ "state": "silence" }));
+                            break 'capture;
+                        }
+                    }
+
                     if all_samples.len() >= max_samples {
                         log::info!("Voice: max duration reached");
                         break;
                     }
-                    if last_voice_time.elapsed().as_millis() as u64 > silence_timeout_ms
-                        && all_samples.len() > 8000
-                    {
-                        log::info!("Voice: silence detected, stopping");
-                        break;
-                    }
                 }
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                     if start.elapsed().as_secs() >= self.max_duration_secs as u64 {
@@ -138,19 +393,15 @@ impl VoiceEngine {
 
         drop(stream);
         recording.store(false, Ordering::Relaxed);
+        let _ = app_handle.emit("voice-state", serde_json::json!({ "state": "stopped" }));
 
         let duration_ms = (all_samples.len() as f64 / 16.0) as u64;
-        log::info!(
-            "Voice: recorded {} samples ({}ms)",
-            all_samples.len(),
-            duration_ms
-        );
+        log::info!("Voice: recorded {} samples ({}ms)", all_samples.len(), duration_ms);
 
         if all_samples.len() < 1600 {
             return Err("Recording too short (< 100ms)".into());
         }
 
-        // Encode to WAV
         let wav_data = encode_wav(&all_samples, 16000)?;
         let wav_base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &wav_data);
 
@@ -211,13 +462,106 @@ impl VoiceEngine {
             .ok_or("No transcription text in response".into())
     }
 
-    /// Request TTS from Gateway and play the audio
+    /// Load a quantized GGML/GGUF Whisper model for offline transcription, if not already
+    /// loaded from this path. Kept resident in `self.whisper` so repeated calls (e.g. every
+    /// wake-word command) don't pay the model load cost again.
+    pub fn load_whisper_model(&mut self, model_path: &str) -> Result<(), String> {
+        if self.whisper_model_path.as_deref() == Some(model_path) && self.whisper.is_some() {
+            return Ok(());
+        }
+
+        log::info!("Voice: loading local Whisper model from {}", model_path);
+        let ctx = whisper_rs::WhisperContext::new_with_params(
+            model_path,
+            whisper_rs::WhisperContextParameters::default(),
+        )
+        .map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+
+        self.whisper = Some(ctx);
+        self.whisper_model_path = Some(model_path.to_string());
+        Ok(())
+    }
+
+    /// Is a local Whisper model loaded from `model_path` ready to transcribe with?
+    pub fn has_local_model(&self, model_path: &str) -> bool {
+        self.whisper.is_some() && self.whisper_model_path.as_deref() == Some(model_path)
+    }
+
+    /// Transcribe previously captured audio entirely on-device via the loaded Whisper model.
+    /// Returns the decoded text and the detected language code (e.g. `"en"`). Call
+    /// `load_whisper_model` first — this never hits the network.
+    pub fn transcribe_local(&self, audio: &CapturedAudio) -> Result<(String, String), String> {
+        let ctx = self
+            .whisper
+            .as_ref()
+            .ok_or("No local Whisper model loaded")?;
+
+        let wav_bytes = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            &audio.wav_base64,
+        )
+        .map_err(|e| format!("Base64 decode error: {}", e))?;
+        let samples = decode_wav_mono_16k(&wav_bytes)?;
+
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| format!("Whisper state init failed: {}", e))?;
+
+        let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(None); // auto-detect
+        params.set_print_progress(false);
+        params.set_print_special(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+
+        state
+            .full(params, &samples)
+            .map_err(|e| format!("Whisper inference failed: {}", e))?;
+
+        let num_segments = state
+            .full_n_segments()
+            .map_err(|e| format!("Whisper segment count failed: {}", e))?;
+        let mut text = String::new();
+        for i in 0..num_segments {
+            let segment = state
+                .full_get_segment_text(i)
+                .map_err(|e| format!("Whisper segment text failed: {}", e))?;
+            text.push_str(segment.trim());
+            text.push(' ');
+        }
+
+        let language = whisper_rs::get_lang_str(state.full_lang_id())
+            .unwrap_or("unknown")
+            .to_string();
+
+        Ok((text.trim().to_string(), language))
+    }
+
+    /// Request TTS from Gateway and play the audio. Backend is controlled by `configure_tts`:
+    /// `Local` skips the Gateway entirely, `LocalFallback` tries the Gateway first and speaks
+    /// locally instead of failing if that call errors or times out, `Gateway` is the original
+    /// always-remote behavior.
     pub async fn speak(
         &self,
         gateway_url: &str,
         jwt_token: &str,
         text: &str,
     ) -> Result<(), String> {
+        if self.tts_backend == TtsBackend::Local {
+            return self.speak_local(text);
+        }
+
+        match self.speak_gateway(gateway_url, jwt_token, text).await {
+            Ok(()) => Ok(()),
+            Err(e) if self.tts_backend == TtsBackend::LocalFallback => {
+                log::warn!("Voice: Gateway TTS failed ({}), falling back to local synthesis", e);
+                self.speak_local(text)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn speak_gateway(&self, gateway_url: &str, jwt_token: &str, text: &str) -> Result<(), String> {
         let url = format!(
             "{}/api/voice/synthesize",
             gateway_url.trim_end_matches('/')
@@ -248,6 +592,242 @@ impl VoiceEngine {
 
         Ok(())
     }
+
+    /// Synthesize and speak `text` entirely on-device via the platform speech engine (SAPI on
+    /// Windows), with no Gateway round-trip — used directly in `Local` mode and as the fallback
+    /// in `LocalFallback` mode.
+    fn speak_local(&self, text: &str) -> Result<(), String> {
+        let mut tts = tts::Tts::default().map_err(|e| format!("Local TTS init failed: {}", e))?;
+
+        if let Some(voice_name) = &self.tts_voice {
+            if let Ok(voices) = tts.voices() {
+                if let Some(voice) = voices.into_iter().find(|v| &v.name() == voice_name) {
+                    let _ = tts.set_voice(&voice);
+                }
+            }
+        }
+        let _ = tts.set_rate(self.tts_rate);
+        let _ = tts.set_pitch(self.tts_pitch);
+
+        tts.speak(text, false)
+            .map_err(|e| format!("Local TTS speak failed: {}", e))?;
+
+        // `tts` speaks asynchronously on most backends; block until done so callers can treat
+        // `speak_local` the same as the blocking Gateway + rodio path above.
+        while tts.is_speaking().unwrap_or(false) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+
+        Ok(())
+    }
+
+    /// Open a full-duplex Opus media session to the Gateway's voice endpoint (negotiated via the
+    /// `voice_identify`/`voice_ready` handshake over the Gateway WS): mic audio streams up in
+    /// 20ms frames while inbound frames are decoded and played continuously, enabling live
+    /// barge-in conversation instead of the record-then-upload flow `record`/`speak` use.
+    pub fn start_stream(
+        &mut self,
+        app_handle: tauri::AppHandle,
+        host: String,
+        port: u16,
+        secret: String,
+        ssrc: u32,
+    ) -> Result<(), String> {
+        use tauri::Emitter;
+
+        if self.stream.is_some() {
+            return Err("Voice stream already active".into());
+        }
+
+        let socket = std::net::UdpSocket::bind("0.0.0.0:0")
+            .map_err(|e| format!("UDP bind failed: {}", e))?;
+        socket
+            .connect((host.as_str(), port))
+            .map_err(|e| format!("UDP connect failed: {}", e))?;
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_millis(200)))
+            .map_err(|e| format!("UDP socket config failed: {}", e))?;
+
+        let active = Arc::new(AtomicBool::new(true));
+
+        let host_dev = cpal::default_host();
+        let input_device = host_dev
+            .default_input_device()
+            .ok_or("No audio input device")?;
+        let config = cpal::StreamConfig {
+            channels: 1,
+            sample_rate: cpal::SampleRate(48000),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
+        let (frame_tx, frame_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(64);
+        let input_stream = input_device
+            .build_input_stream(
+                &config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    let _ = frame_tx.try_send(data.to_vec());
+                },
+                |err| log::error!("Voice stream: capture error: {}", err),
+                None,
+            )
+            .map_err(|e| format!("Failed to build input stream: {}", e))?;
+        input_stream
+            .play()
+            .map_err(|e| format!("Failed to start voice stream capture: {}", e))?;
+
+        let send_socket = socket
+            .try_clone()
+            .map_err(|e| format!("UDP socket clone failed: {}", e))?;
+        let send_active = active.clone();
+        let send_secret = secret.clone();
+        let app_handle_send = app_handle.clone();
+        std::thread::spawn(move || {
+            // Keep the capture stream alive for the life of this thread — dropping it stops it.
+            let _input_stream = input_stream;
+            let mut encoder =
+                match opus::Encoder::new(48000, opus::Channels::Mono, opus::Application::Voip) {
+                    Ok(e) => e,
+                    Err(e) => {
+                        log::error!("Voice stream: Opus encoder init failed: {}", e);
+                        return;
+                    }
+                };
+
+            const FRAME_SAMPLES: usize = 960; // 20ms @ 48kHz
+            let mut pending: Vec<f32> = Vec::new();
+            let mut seq: u64 = 0;
+
+            while send_active.load(Ordering::Relaxed) {
+                match frame_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+                    Ok(samples) => pending.extend_from_slice(&samples),
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(_) => break,
+                }
+
+                while pending.len() >= FRAME_SAMPLES {
+                    let frame: Vec<f32> = pending.drain(..FRAME_SAMPLES).collect();
+                    let mut encoded = vec![0u8; 4000];
+                    match encoder.encode_float(&frame, &mut encoded) {
+                        Ok(len) => {
+                            encoded.truncate(len);
+                            xor_keystream(&send_secret, seq, &mut encoded);
+
+                            let mut packet = Vec::with_capacity(encoded.len() + 12);
+                            packet.extend_from_slice(&ssrc.to_be_bytes());
+                            packet.extend_from_slice(&seq.to_be_bytes());
+                            packet.extend_from_slice(&encoded);
+
+                            if send_socket.send(&packet).is_err() {
+                                log::warn!("Voice stream: UDP send failed, stopping");
+                                send_active.store(false, Ordering::Relaxed);
+                            }
+                            seq += 1;
+                        }
+                        Err(e) => log::warn!("Voice stream: Opus encode failed: {}", e),
+                    }
+                }
+            }
+
+            let _ = app_handle_send.emit("voice-stream-state", serde_json::json!({ "state": "closed" }));
+            log::info!("Voice stream: outbound thread exiting");
+        });
+
+        let recv_socket = socket;
+        let recv_active = active.clone();
+        let recv_secret = secret;
+        std::thread::spawn(move || {
+            let (_output_stream, stream_handle) = match rodio::OutputStream::try_default() {
+                Ok(s) => s,
+                Err(e) => {
+                    log::error!("Voice stream: audio output error: {}", e);
+                    return;
+                }
+            };
+            let sink = match rodio::Sink::try_new(&stream_handle) {
+                Ok(s) => s,
+                Err(e) => {
+                    log::error!("Voice stream: sink error: {}", e);
+                    return;
+                }
+            };
+
+            let mut decoder = match opus::Decoder::new(48000, opus::Channels::Mono) {
+                Ok(d) => d,
+                Err(e) => {
+                    log::error!("Voice stream: Opus decoder init failed: {}", e);
+                    return;
+                }
+            };
+
+            let mut buf = [0u8; 4096];
+            while recv_active.load(Ordering::Relaxed) {
+                match recv_socket.recv(&mut buf) {
+                    Ok(n) if n > 12 => {
+                        let seq = u64::from_be_bytes(buf[4..12].try_into().unwrap());
+                        let mut payload = buf[12..n].to_vec();
+                        xor_keystream(&recv_secret, seq, &mut payload);
+
+                        let mut pcm = vec![0f32; 5760]; // max Opus frame size @ 48kHz
+                        match decoder.decode_float(&payload, &mut pcm, false) {
+                            Ok(decoded) => {
+                                pcm.truncate(decoded);
+                                sink.append(rodio::buffer::SamplesBuffer::new(1, 48000, pcm));
+                            }
+                            Err(e) => log::warn!("Voice stream: Opus decode failed: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(ref e)
+                        if e.kind() == std::io::ErrorKind::WouldBlock
+                            || e.kind() == std::io::ErrorKind::TimedOut => {}
+                    Err(e) => {
+                        log::warn!("Voice stream: UDP recv failed: {}", e);
+                        break;
+                    }
+                }
+            }
+            log::info!("Voice stream: inbound thread exiting");
+        });
+
+        let _ = app_handle.emit("voice-stream-state", serde_json::json!({ "state": "ready" }));
+        self.stream = Some(StreamHandle { active });
+        Ok(())
+    }
+
+    /// Tear down the live voice stream, if any. Safe to call even if no stream is active.
+    pub fn stop_stream(&mut self) {
+        if let Some(handle) = self.stream.take() {
+            handle.active.store(false, Ordering::Relaxed);
+            log::info!("Voice stream: stop requested");
+        }
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+}
+
+/// XORs `buf` with a SHA-256-derived keystream seeded by `secret` and `frame_index`, so replaying
+/// or tampering with one media packet doesn't reveal anything about another. This rides inside a
+/// media session whose endpoint/secret were only ever handed out over the already-authenticated
+/// Gateway WS, so it's meant as frame-level obfuscation rather than a standalone cipher suite —
+/// reuses the `sha2` dependency already pulled in for `hash_file` instead of adding a new one.
+fn xor_keystream(secret: &str, frame_index: u64, buf: &mut [u8]) {
+    use sha2::{Digest, Sha256};
+    let mut offset = 0usize;
+    let mut counter = frame_index;
+    while offset < buf.len() {
+        let mut hasher = Sha256::new();
+        hasher.update(secret.as_bytes());
+        hasher.update(counter.to_be_bytes());
+        let block = hasher.finalize();
+        let n = (buf.len() - offset).min(block.len());
+        for i in 0..n {
+            buf[offset + i] ^= block[i];
+        }
+        offset += n;
+        counter += 1;
+    }
 }
 
 /// Encode f32 samples to WAV bytes
@@ -278,6 +858,402 @@ fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, String> {
     Ok(buffer)
 }
 
+/// WebRTC-style frame voice-activity detector: splits each 16kHz frame into three crude
+/// frequency sub-bands (low/mid/high, via a cascade of one-pole low-pass filters) so steady
+/// background hum — which concentrates its energy in one band — doesn't read as voiced the way
+/// a single full-band RMS threshold would. A frame counts as voiced when at least two of the
+/// three sub-bands exceed that band's rolling noise floor by `aggressiveness`; the floor itself
+/// only adapts on unvoiced frames so a long utterance doesn't drag the floor up to meet it.
+struct FrameVad {
+    aggressiveness: f32,
+    floor: [f32; 3],
+}
+
+impl FrameVad {
+    fn new(aggressiveness: f32) -> Self {
+        Self { aggressiveness, floor: [1.0; 3] }
+    }
+
+    fn is_voiced(&mut self, frame: &[i16]) -> bool {
+        let bands = sub_band_energies(frame);
+        let voiced_bands = (0..3).filter(|&i| bands[i] > self.floor[i] * self.aggressiveness).count();
+        let voiced = voiced_bands >= 2;
+
+        if !voiced {
+            for i in 0..3 {
+                self.floor[i] = 0.95 * self.floor[i] + 0.05 * bands[i];
+            }
+        }
+
+        voiced
+    }
+}
+
+/// Crude low/mid/high energy split of one VAD frame via a cascade of one-pole low-pass filters:
+/// `low` is the heavily-smoothed signal, `mid` is the difference between a lightly- and a
+/// heavily-smoothed signal (a cheap band-pass), and `high` is what's left after removing the
+/// lightly-smoothed signal (a cheap high-pass residual). Good enough to tell "energy concentrated
+/// in one band" (hum) from "energy spread across bands" (speech) without pulling in an FFT.
+fn sub_band_energies(frame: &[i16]) -> [f32; 3] {
+    let samples: Vec<f32> = frame.iter().map(|&s| s as f32).collect();
+    let heavy = one_pole_lowpass(&samples, 0.05);
+    let light = one_pole_lowpass(&samples, 0.25);
+
+    let energy = |v: &[f32]| -> f32 {
+        if v.is_empty() {
+            return 0.0;
+        }
+        v.iter().map(|s| s * s).sum::<f32>() / v.len() as f32
+    };
+
+    let mid: Vec<f32> = light.iter().zip(heavy.iter()).map(|(l, h)| l - h).collect();
+    let high: Vec<f32> = samples.iter().zip(light.iter()).map(|(x, l)| x - l).collect();
+
+    [energy(&heavy), energy(&mid), energy(&high)]
+}
+
+fn one_pole_lowpass(samples: &[f32], alpha: f32) -> Vec<f32> {
+    let mut state = 0.0f32;
+    samples
+        .iter()
+        .map(|&x| {
+            state += alpha * (x - state);
+            state
+        })
+        .collect()
+}
+
+/// Single-channel spectral noise gate: overlapping Hann-windowed frames are forward-FFT'd, a
+/// per-bin noise spectrum is estimated from the first ~300ms (assumed to be room noise before
+/// speech starts), each frame's magnitude is reduced by `alpha` times that noise estimate but
+/// never below `beta` of its original magnitude (to avoid "musical noise" from over-subtraction),
+/// phase is left untouched, and frames are inverse-FFT'd and overlap-added back together.
+const DENOISE_FRAME_SIZE: usize = 512;
+const DENOISE_HOP_SIZE: usize = DENOISE_FRAME_SIZE / 2;
+const DENOISE_NOISE_ESTIMATE_MS: usize = 300;
+
+fn hann_window(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (len - 1) as f32).cos())
+        .collect()
+}
+
+fn spectral_denoise(samples: &[f32], sample_rate: u32, alpha: f32, beta: f32) -> Vec<f32> {
+    use num_complex::Complex32;
+
+    if samples.len() < DENOISE_FRAME_SIZE {
+        return samples.to_vec();
+    }
+
+    let mut planner = realfft::RealFftPlanner::<f32>::new();
+    let r2c = planner.plan_fft_forward(DENOISE_FRAME_SIZE);
+    let c2r = planner.plan_fft_inverse(DENOISE_FRAME_SIZE);
+    let window = hann_window(DENOISE_FRAME_SIZE);
+    let num_bins = DENOISE_FRAME_SIZE / 2 + 1;
+
+    let num_frames = (samples.len() - DENOISE_FRAME_SIZE) / DENOISE_HOP_SIZE + 1;
+    let noise_frame_count = ((sample_rate as usize * DENOISE_NOISE_ESTIMATE_MS / 1000)
+        / DENOISE_HOP_SIZE)
+        .clamp(1, num_frames);
+
+    // Pass 1: FFT every frame up front (so pass 2 can estimate the noise spectrum from the first
+    // few frames before gating any of them, including those same first frames).
+    let mut spectra: Vec<Vec<Complex32>> = Vec::with_capacity(num_frames);
+    let mut noise_mag = vec![0f32; num_bins];
+
+    for i in 0..num_frames {
+        let start = i * DENOISE_HOP_SIZE;
+        let mut windowed: Vec<f32> = samples[start..start + DENOISE_FRAME_SIZE]
+            .iter()
+            .zip(&window)
+            .map(|(s, w)| s * w)
+            .collect();
+        let mut spectrum = r2c.make_output_vec();
+        if r2c.process(&mut windowed, &mut spectrum).is_err() {
+            return samples.to_vec();
+        }
+        if i < noise_frame_count {
+            for (bin, acc) in spectrum.iter().zip(noise_mag.iter_mut()) {
+                *acc += bin.norm();
+            }
+        }
+        spectra.push(spectrum);
+    }
+    for m in &mut noise_mag {
+        *m /= noise_frame_count as f32;
+    }
+
+    // Pass 2: spectral subtraction per frame, inverse FFT, overlap-add with window normalization.
+    let mut output = vec![0f32; samples.len()];
+    let mut window_energy = vec![0f32; samples.len()];
+
+    for (i, spectrum) in spectra.iter_mut().enumerate() {
+        for (bin, &noise) in spectrum.iter_mut().zip(noise_mag.iter()) {
+            let mag = bin.norm();
+            let phase = bin.arg();
+            let gated = (mag - alpha * noise).max(beta * mag);
+            *bin = Complex32::from_polar(gated, phase);
+        }
+
+        let mut time_domain = c2r.make_output_vec();
+        if c2r.process(spectrum, &mut time_domain).is_err() {
+            return samples.to_vec();
+        }
+
+        let start = i * DENOISE_HOP_SIZE;
+        for (j, &sample) in time_domain.iter().enumerate() {
+            // realfft's inverse transform is unnormalized, hence the 1/N scale.
+            let w = window[j];
+            output[start + j] += sample / DENOISE_FRAME_SIZE as f32 * w;
+            window_energy[start + j] += w * w;
+        }
+    }
+
+    for (o, w) in output.iter_mut().zip(window_energy.iter()) {
+        if *w > 1e-6 {
+            *o /= w;
+        }
+    }
+
+    output
+}
+
+/// Decode WAV bytes to f32 mono samples for Whisper, resampling to 16kHz if the source differs
+/// (recordings always come in at 16kHz via `record`, but locally-supplied audio may not).
+fn decode_wav_mono_16k(wav_bytes: &[u8]) -> Result<Vec<f32>, String> {
+    let mut reader = hound::WavReader::new(Cursor::new(wav_bytes))
+        .map_err(|e| format!("WAV reader error: {}", e))?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Int => reader
+            .samples::<i32>()
+            .map(|s| s.map(|v| v as f32 / (1i64 << (spec.bits_per_sample - 1)) as f32))
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|e| format!("WAV sample read error: {}", e))?,
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|e| format!("WAV sample read error: {}", e))?,
+    };
+
+    let mono: Vec<f32> = if spec.channels > 1 {
+        samples
+            .chunks(spec.channels as usize)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    } else {
+        samples
+    };
+
+    if spec.sample_rate == 16000 {
+        return Ok(mono);
+    }
+
+    // Simple linear resampler — good enough for speech; avoids pulling in a resampling crate.
+    let ratio = 16000.0 / spec.sample_rate as f64;
+    let out_len = (mono.len() as f64 * ratio) as usize;
+    let mut resampled = Vec::with_capacity(out_len);
+    for i in 0..out_len {
+        let src_pos = i as f64 / ratio;
+        let idx = src_pos as usize;
+        let frac = (src_pos - idx as f64) as f32;
+        let a = mono.get(idx).copied().unwrap_or(0.0);
+        let b = mono.get(idx + 1).copied().unwrap_or(a);
+        resampled.push(a + (b - a) * frac);
+    }
+    Ok(resampled)
+}
+
+/// Open the cpal input stream(s) `source` calls for, feeding captured chunks into a single
+/// channel `record` drains the same way regardless of source. The returned `Vec<cpal::Stream>`
+/// must be kept alive for the duration of the recording — dropping a stream stops it.
+///
+/// `input_device_name` selects the microphone by name (as returned by `list_input_devices`),
+/// falling back to the default device if unset or not found — only the microphone leg of
+/// `Mixed`/`Microphone` honors it; the loopback leg always uses the default output device.
+///
+/// `Mixed` opens both the microphone and loopback streams and sums same-tick chunks together in
+/// a dedicated mixer thread before forwarding, since `record`'s capture loop only knows how to
+/// drain one channel.
+fn open_capture_streams(
+    source: CaptureSource,
+    input_device_name: Option<&str>,
+) -> Result<(Vec<cpal::Stream>, std::sync::mpsc::Receiver<Vec<f32>>), String> {
+    let host = cpal::default_host();
+
+    match source {
+        CaptureSource::Microphone => {
+            let device = select_input_device(&host, input_device_name)?;
+            let (stream, rx) = build_capture_stream(&device, "input")?;
+            Ok((vec![stream], rx))
+        }
+        CaptureSource::SystemAudio => {
+            let device = loopback_device(&host)?;
+            let (stream, rx) = build_capture_stream(&device, "loopback")?;
+            Ok((vec![stream], rx))
+        }
+        CaptureSource::Mixed => {
+            let mic_device = select_input_device(&host, input_device_name)?;
+            let sys_device = loopback_device(&host)?;
+
+            let (mic_stream, mic_rx) = build_capture_stream(&mic_device, "input")?;
+            let (sys_stream, sys_rx) = build_capture_stream(&sys_device, "loopback")?;
+
+            let (mix_tx, mix_rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(64);
+            std::thread::spawn(move || loop {
+                let mic_chunk = mic_rx.recv_timeout(std::time::Duration::from_millis(200));
+                let sys_chunk = sys_rx.recv_timeout(std::time::Duration::from_millis(200));
+                if mic_chunk.is_err() && sys_chunk.is_err() {
+                    break;
+                }
+                let mic_chunk = mic_chunk.unwrap_or_default();
+                let sys_chunk = sys_chunk.unwrap_or_default();
+                let len = mic_chunk.len().max(sys_chunk.len());
+                let mut mixed = Vec::with_capacity(len);
+                for i in 0..len {
+                    let m = mic_chunk.get(i).copied().unwrap_or(0.0);
+                    let s = sys_chunk.get(i).copied().unwrap_or(0.0);
+                    mixed.push((m + s).clamp(-1.0, 1.0));
+                }
+                if mix_tx.try_send(mixed).is_err() {
+                    break;
+                }
+            });
+
+            Ok((vec![mic_stream, sys_stream], mix_rx))
+        }
+    }
+}
+
+/// Pick the named input device, falling back to the host default (with a warning) if `name` is
+/// `None` or doesn't match any currently-connected device.
+fn select_input_device(host: &cpal::Host, name: Option<&str>) -> Result<cpal::Device, String> {
+    if let Some(name) = name {
+        if let Ok(mut devices) = host.input_devices() {
+            if let Some(device) = devices.find(|d| d.name().map(|n| n == name).unwrap_or(false)) {
+                return Ok(device);
+            }
+        }
+        log::warn!(
+            "Voice: configured input device '{}' not found, falling back to default",
+            name
+        );
+    }
+    host.default_input_device().ok_or_else(|| "No audio input device".to_string())
+}
+
+/// The default output device, opened as an input-capable device for WASAPI loopback capture —
+/// cpal's loopback/monitor support surfaces a host's render device as an ordinary capturable
+/// `Device` where the platform allows it.
+fn loopback_device(host: &cpal::Host) -> Result<cpal::Device, String> {
+    host.default_output_device()
+        .ok_or_else(|| "No audio output device available for system-audio loopback".to_string())
+}
+
+/// Build an input stream on `device`, preferring a native 16kHz mono config but falling back to
+/// whatever the device actually supports (some USB headsets reject a forced 16kHz mono request
+/// outright) — each captured chunk is downmixed and resampled to 16kHz mono in the callback
+/// itself, so every consumer downstream of the returned channel can keep assuming that format.
+fn build_capture_stream(
+    device: &cpal::Device,
+    label: &str,
+) -> Result<(cpal::Stream, std::sync::mpsc::Receiver<Vec<f32>>), String> {
+    let wants_16k_mono = device
+        .supported_input_configs()
+        .map(|mut configs| {
+            configs.any(|c| {
+                c.channels() == 1
+                    && c.min_sample_rate().0 <= 16000
+                    && c.max_sample_rate().0 >= 16000
+            })
+        })
+        .unwrap_or(false);
+
+    let (config, native_channels, native_rate) = if wants_16k_mono {
+        (
+            cpal::StreamConfig {
+                channels: 1,
+                sample_rate: cpal::SampleRate(16000),
+                buffer_size: cpal::BufferSize::Default,
+            },
+            1u16,
+            16000u32,
+        )
+    } else {
+        let default_config = device
+            .default_input_config()
+            .map_err(|e| format!("No usable {} config: {}", label, e))?;
+        let channels = default_config.channels();
+        let rate = default_config.sample_rate().0;
+        log::info!(
+            "Voice: {} device doesn't support 16kHz mono natively, capturing at {}ch/{}Hz and resampling",
+            label, channels, rate
+        );
+        (
+            cpal::StreamConfig {
+                channels,
+                sample_rate: cpal::SampleRate(rate),
+                buffer_size: cpal::BufferSize::Default,
+            },
+            channels,
+            rate,
+        )
+    };
+
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<f32>>(64);
+    let error_label = label.to_string();
+    let stream = device
+        .build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let chunk = resample_and_downmix(data, native_channels, native_rate, 16000);
+                let _ = tx.try_send(chunk);
+            },
+            move |err| log::error!("Audio capture error ({}): {}", error_label, err),
+            None,
+        )
+        .map_err(|e| format!("Failed to build {} stream: {}", label, e))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start {} capture: {}", label, e))?;
+
+    Ok((stream, rx))
+}
+
+/// Downmix a captured chunk to mono and linearly resample it to `target_rate`. Resampling
+/// per-chunk rather than over the whole recording means a little boundary error at each chunk
+/// edge, but chunks are small (tens of ms) so it's inaudible and more than good enough for STT —
+/// the same "good enough, skip the DSP library" tradeoff `decode_wav_mono_16k` makes.
+fn resample_and_downmix(data: &[f32], channels: u16, native_rate: u32, target_rate: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let mono: Vec<f32> = if channels <= 1 {
+        data.to_vec()
+    } else {
+        data.chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    };
+
+    if native_rate == target_rate || mono.len() < 2 {
+        return mono;
+    }
+
+    let ratio = target_rate as f64 / native_rate as f64;
+    let out_len = ((mono.len() as f64) * ratio).round() as usize;
+    (0..out_len)
+        .map(|i| {
+            let src_pos = i as f64 / ratio;
+            let idx = (src_pos as usize).min(mono.len() - 1);
+            let frac = (src_pos - idx as f64) as f32;
+            let a = mono[idx];
+            let b = mono.get(idx + 1).copied().unwrap_or(a);
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
 /// Play audio bytes (WAV format) through the default output device
 fn play_audio(audio_bytes: &[u8]) -> Result<(), String> {
     let (_stream, stream_handle) = rodio::OutputStream::try_default()
@@ -303,3 +1279,46 @@ pub fn list_output_devices() -> Vec<String> {
         .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
         .unwrap_or_default()
 }
+
+/// List available audio input devices, for `set_input_device`.
+pub fn list_input_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    host.input_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn input_device_config_path() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|d| d.join("forgeai-companion").join("voice-settings.json"))
+}
+
+fn load_persisted_input_device() -> Option<String> {
+    let path = input_device_config_path()?;
+    let json = std::fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    value.get("inputDevice")?.as_str().map(|s| s.to_string())
+}
+
+fn save_persisted_input_device(name: Option<&str>) -> Result<(), String> {
+    let path = input_device_config_path()
+        .ok_or("Could not determine config directory")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let json = serde_json::json!({ "inputDevice": name }).to_string();
+    std::fs::write(&path, json).map_err(|e| format!("Failed to save voice settings: {}", e))
+}
+
+/// List voice names the local TTS backend (SAPI on Windows) has installed, for `configure_tts`.
+pub fn list_tts_voices() -> Vec<String> {
+    match tts::Tts::default() {
+        Ok(tts) => tts
+            .voices()
+            .map(|voices| voices.iter().map(|v| v.name()).collect())
+            .unwrap_or_default(),
+        Err(e) => {
+            log::warn!("Voice: local TTS unavailable, no voices to list: {}", e);
+            Vec::new()
+        }
+    }
+}
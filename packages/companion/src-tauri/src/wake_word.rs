@@ -3,12 +3,31 @@
 //! Uses Picovoice Porcupine for on-device wake word detection.
 //! Default keyword: "Hey Forge" (customizable).
 //! Runs in a background thread, consuming <1% CPU when idle.
-//! When triggered, emits an event to the Tauri frontend.
+//! When triggered, emits an event to the Tauri frontend and opens a voice turn: captured audio
+//! streams to the Gateway as `audio_chunk` messages until a lightweight VAD (see
+//! `VoiceActivityDetector`) decides the user has stopped talking.
 
+use base64::Engine as _;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+
+/// How long a run of consecutive non-speech frames must last before a voice turn is considered
+/// over.
+const VOICE_TURN_SILENCE_MS: u64 = 800;
+/// Hard cap on a voice turn's length, regardless of VAD state, so a stuck noise floor can't pin
+/// the microphone open indefinitely.
+const VOICE_TURN_MAX_MS: u64 = 8_000;
+/// A frame is "speech" once its energy exceeds the adaptive noise floor by this ratio.
+const VAD_ENERGY_RATIO: f64 = 2.5;
+/// Floor under the adaptive noise estimate, so near-silent input (noise floor ~0) doesn't make
+/// the ratio test trivially true for the faintest sound.
+const VAD_NOISE_FLOOR_MIN: f64 = 50.0;
+/// How quickly the noise floor tracks quiet frames — low alpha so a brief loud frame (speech)
+/// doesn't drag the floor up and desensitize the detector.
+const VAD_NOISE_FLOOR_ALPHA: f64 = 0.05;
 
 /// Wake word engine state
 pub struct WakeWordEngine {
@@ -16,6 +35,11 @@ pub struct WakeWordEngine {
     sensitivity: f32,
     access_key: Option<String>,
     keyword_path: Option<String>,
+    /// Outgoing sender for the live Gateway connection (see `commands::first_active_outbound_sender`,
+    /// wired in on `wake_word_start`). When set, each voice turn's captured audio is streamed out
+    /// as `audio_chunk` messages; when `None`, wake-word detection still fires events but no audio
+    /// is forwarded anywhere.
+    gateway_tx: Option<mpsc::UnboundedSender<String>>,
 }
 
 /// Event emitted when wake word is detected
@@ -42,9 +66,16 @@ impl WakeWordEngine {
             sensitivity: 0.5,
             access_key: None,
             keyword_path: None,
+            gateway_tx: None,
         }
     }
 
+    /// Wire this engine to a live Gateway connection so detected voice turns stream audio out as
+    /// `audio_chunk` messages. Pass `None` to stop forwarding (e.g. once the Gateway disconnects).
+    pub fn set_gateway_sender(&mut self, tx: Option<mpsc::UnboundedSender<String>>) {
+        self.gateway_tx = tx;
+    }
+
     /// Configure the engine with Picovoice access key
     pub fn configure(&mut self, access_key: String, sensitivity: f32) {
         self.access_key = Some(access_key);
@@ -85,6 +116,7 @@ impl WakeWordEngine {
         let sensitivity = self.sensitivity;
         let running = self.running.clone();
         let keyword_path = self.keyword_path.clone();
+        let gateway_tx = self.gateway_tx.clone();
 
         running.store(true, Ordering::Relaxed);
 
@@ -95,6 +127,7 @@ impl WakeWordEngine {
                 keyword_path.as_deref(),
                 &running,
                 &app_handle,
+                gateway_tx,
             ) {
                 log::error!("Wake word engine error: {}", e);
                 running.store(false, Ordering::Relaxed);
@@ -127,6 +160,7 @@ fn run_detection_loop(
     keyword_path: Option<&str>,
     running: &Arc<AtomicBool>,
     app_handle: &AppHandle,
+    gateway_tx: Option<mpsc::UnboundedSender<String>>,
 ) -> Result<(), String> {
     // Initialize Porcupine
     let porcupine = if let Some(kw_path) = keyword_path {
@@ -205,6 +239,7 @@ fn run_detection_loop(
                     match porcupine.process(&frame) {
                         Ok(keyword_index) if keyword_index >= 0 => {
                             log::info!("Wake word detected! (keyword index: {})", keyword_index);
+                            crate::metrics::record_wake_word_detection();
 
                             let event = WakeWordEvent {
                                 keyword: "Hey Forge".to_string(),
@@ -213,8 +248,17 @@ fn run_detection_loop(
 
                             let _ = app_handle.emit("wake-word-detected", event);
 
-                            // Brief cooldown to prevent repeated triggers
-                            std::thread::sleep(std::time::Duration::from_secs(2));
+                            // Keep capturing and streaming audio until the VAD hears sustained
+                            // silence — the turn itself doubles as the old fixed cooldown.
+                            run_voice_turn(
+                                &rx,
+                                &mut buffer,
+                                frame_length,
+                                sample_rate,
+                                running,
+                                &gateway_tx,
+                                app_handle,
+                            );
                         }
                         Ok(_) => {} // No detection
                         Err(e) => {
@@ -233,6 +277,113 @@ fn run_detection_loop(
     Ok(())
 }
 
+/// Per-frame short-time energy (mean of squared samples) classified against an adaptive noise
+/// floor — cheap enough to run inline in the detection loop alongside Porcupine.
+struct VoiceActivityDetector {
+    noise_floor: f64,
+}
+
+impl VoiceActivityDetector {
+    fn new() -> Self {
+        Self { noise_floor: VAD_NOISE_FLOOR_MIN }
+    }
+
+    /// Classify a frame as speech/non-speech, adapting the noise floor toward non-speech frames
+    /// as it goes.
+    fn is_speech(&mut self, frame: &[i16]) -> bool {
+        let energy = frame_energy(frame);
+        let threshold = self.noise_floor.max(VAD_NOISE_FLOOR_MIN) * VAD_ENERGY_RATIO;
+        let speech = energy > threshold;
+        if !speech {
+            self.noise_floor =
+                self.noise_floor * (1.0 - VAD_NOISE_FLOOR_ALPHA) + energy * VAD_NOISE_FLOOR_ALPHA;
+        }
+        speech
+    }
+}
+
+fn frame_energy(frame: &[i16]) -> f64 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+    let sum_sq: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    sum_sq / frame.len() as f64
+}
+
+/// Keep pulling audio frames and forwarding them to the Gateway as `audio_chunk` messages until
+/// the VAD reports `VOICE_TURN_SILENCE_MS` of consecutive non-speech, or `VOICE_TURN_MAX_MS`
+/// elapses regardless. Emits `voice-turn-start`/`voice-turn-end` so the frontend can show a
+/// listening indicator for the turn's duration.
+fn run_voice_turn(
+    rx: &std::sync::mpsc::Receiver<Vec<i16>>,
+    buffer: &mut Vec<i16>,
+    frame_length: usize,
+    sample_rate: u32,
+    running: &Arc<AtomicBool>,
+    gateway_tx: &Option<mpsc::UnboundedSender<String>>,
+    app_handle: &AppHandle,
+) {
+    let _ = app_handle.emit("voice-turn-start", ());
+    log::info!("Voice turn started");
+
+    let mut vad = VoiceActivityDetector::new();
+    let frame_duration_ms = (frame_length as u64 * 1000) / (sample_rate as u64).max(1);
+    let mut silence_ms: u64 = 0;
+    let mut elapsed_ms: u64 = 0;
+
+    'turn: while running.load(Ordering::Relaxed) {
+        match rx.recv_timeout(std::time::Duration::from_millis(100)) {
+            Ok(samples) => {
+                buffer.extend_from_slice(&samples);
+
+                while buffer.len() >= frame_length {
+                    let frame: Vec<i16> = buffer.drain(..frame_length).collect();
+                    forward_audio_chunk(gateway_tx, &frame, sample_rate);
+
+                    if vad.is_speech(&frame) {
+                        silence_ms = 0;
+                    } else {
+                        silence_ms += frame_duration_ms;
+                    }
+                    elapsed_ms += frame_duration_ms;
+
+                    if silence_ms >= VOICE_TURN_SILENCE_MS || elapsed_ms >= VOICE_TURN_MAX_MS {
+                        break 'turn;
+                    }
+                }
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                elapsed_ms += 100;
+                if elapsed_ms >= VOICE_TURN_MAX_MS {
+                    break;
+                }
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let _ = app_handle.emit("voice-turn-end", ());
+    log::info!("Voice turn ended ({}ms elapsed)", elapsed_ms);
+}
+
+/// Base64-encode a frame of little-endian i16 PCM samples and send it as an `audio_chunk`
+/// message. A no-op when no Gateway connection is wired up (`gateway_tx` is `None`).
+fn forward_audio_chunk(gateway_tx: &Option<mpsc::UnboundedSender<String>>, frame: &[i16], sample_rate: u32) {
+    let Some(tx) = gateway_tx else { return };
+
+    let bytes: Vec<u8> = frame.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let pcm_base64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+    let msg = serde_json::json!({
+        "type": "audio_chunk",
+        "pcm": pcm_base64,
+        "sampleRate": sample_rate,
+    });
+    if let Ok(json) = serde_json::to_string(&msg) {
+        let _ = tx.send(json);
+    }
+}
+
 /// Get available audio input devices
 pub fn list_audio_devices() -> Vec<String> {
     let host = cpal::default_host();
@@ -244,3 +395,41 @@ pub fn list_audio_devices() -> Vec<String> {
         })
         .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_audio_chunk_sends_when_wired() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let gateway_tx = Some(tx);
+        let frame: Vec<i16> = vec![100, -200, 300, -400];
+
+        forward_audio_chunk(&gateway_tx, &frame, 16_000);
+
+        let sent = rx.try_recv().expect("a message should have been sent");
+        let parsed: serde_json::Value = serde_json::from_str(&sent).unwrap();
+        assert_eq!(parsed["type"], "audio_chunk");
+        assert_eq!(parsed["sampleRate"], 16_000);
+        assert!(parsed["pcm"].as_str().is_some_and(|s| !s.is_empty()));
+    }
+
+    #[test]
+    fn test_forward_audio_chunk_noop_when_unwired() {
+        let gateway_tx: Option<mpsc::UnboundedSender<String>> = None;
+        // Must not panic when no Gateway connection is wired up.
+        forward_audio_chunk(&gateway_tx, &[1, 2, 3], 16_000);
+    }
+
+    #[test]
+    fn test_set_gateway_sender_wires_a_live_sender() {
+        let mut engine = WakeWordEngine::new();
+        assert!(engine.gateway_tx.is_none());
+
+        let (tx, _rx) = mpsc::unbounded_channel::<String>();
+        engine.set_gateway_sender(Some(tx));
+
+        assert!(engine.gateway_tx.is_some());
+    }
+}